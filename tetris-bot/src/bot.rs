@@ -0,0 +1,308 @@
+use std::collections::VecDeque;
+
+use tetris_core::{
+    action::Action,
+    board::Board,
+    brick::Brick,
+    piece_set::PieceSet,
+    placement::{self, Placement},
+    player::Player,
+    ruleset::{HoldRule, Ruleset},
+    tetris::Tetris,
+    traits::Randomizer,
+};
+
+use crate::evaluation::Weights;
+
+/// The first move of the best line of play found.
+#[derive(Debug, Clone)]
+pub struct Plan {
+    /// Whether to hold before moving the piece.
+    pub hold: bool,
+    pub placement: Placement,
+}
+
+/// One line of play being looked at.
+#[derive(Debug, Clone)]
+struct Node {
+    board: Board,
+    held: Option<Brick>,
+    /// Index of the first queue piece not placed yet.
+    next: usize,
+    /// Points for the lines cleared on the way here.
+    cleared: f32,
+    value: f32,
+    first: Plan,
+}
+
+/// Plays a game by searching every placement of the current piece and the preview.
+#[derive(Debug, Clone)]
+pub struct Bot {
+    weights: Weights,
+    /// Preview pieces placed after the current one before judging a line of play. One by
+    /// default: every further piece costs about as much search again, and the whole
+    /// guideline preview takes several frames per piece, long enough to stutter the game.
+    /// [`Bot::with_lookahead`] goes deeper where time allows, e.g. in the simulator.
+    lookahead: usize,
+    /// Lines of play kept at every depth of the search.
+    beam: usize,
+    /// Cells the current piece is headed for, unset once it has been dropped.
+    target: Option<Vec<(i32, i32)>>,
+    inputs: VecDeque<Action>,
+    /// Where the piece is after the last input, if nothing else moved it.
+    expected: Option<Player>,
+}
+
+impl Default for Bot {
+    fn default() -> Self {
+        Self::new(Weights::default())
+    }
+}
+
+impl Bot {
+    pub fn new(weights: Weights) -> Self {
+        Self {
+            weights,
+            lookahead: 1,
+            beam: 8,
+            target: None,
+            inputs: VecDeque::new(),
+            expected: None,
+        }
+    }
+
+    pub fn with_lookahead(self, lookahead: usize, beam: usize) -> Self {
+        Self {
+            lookahead,
+            beam: beam.max(1),
+            ..self
+        }
+    }
+
+    /// The next input to send to `tetris`, one per call. Nothing while no piece is in play.
+    pub fn next_action<R: Randomizer>(&mut self, tetris: &Tetris<R>) -> Option<Action> {
+        if tetris.game_over() || tetris.spawning() {
+            return None;
+        }
+
+        let player = tetris.player();
+        // Gravity or a kick may have moved the piece somewhere the inputs did not expect.
+        if !self.expected.is_some_and(|e| same_state(&e, &player)) {
+            self.route(tetris);
+        }
+
+        let action = self.inputs.pop_front()?;
+        self.expected = match action {
+            Action::Hold => None,
+            Action::HardDrop => {
+                self.target = None;
+                None
+            }
+            _ => placement::step(
+                tetris.board(),
+                &player,
+                action,
+                tetris.ruleset(),
+                tetris.pieces(),
+            ),
+        };
+        Some(action)
+    }
+
+    /// Finds the inputs from where the piece is now to the target, picking a new target
+    /// if it is unset or out of reach.
+    fn route<R: Randomizer>(&mut self, tetris: &Tetris<R>) {
+        let path = self.target.as_ref().and_then(|target| {
+            tetris
+                .placements()
+                .into_iter()
+                .find(|p| p.cells() == *target)
+        });
+        if let Some(path) = path {
+            self.inputs = path.inputs.into();
+            return;
+        }
+
+        match self.plan(tetris) {
+            Some(plan) => {
+                self.target = Some(plan.placement.cells());
+                self.inputs = plan.placement.inputs.into();
+                if plan.hold {
+                    self.inputs.push_front(Action::Hold);
+                }
+            }
+            None => {
+                self.target = None;
+                self.inputs = VecDeque::from([Action::HardDrop]);
+            }
+        }
+    }
+
+    /// Searches the placements of the current piece, with and without holding, and of the
+    /// preview pieces after it, returning the first move of the best line found.
+    pub fn plan<R: Randomizer>(&self, tetris: &Tetris<R>) -> Option<Plan> {
+        let board = tetris.board();
+        let ruleset = tetris.ruleset();
+        let pieces = tetris.pieces();
+        let queue = tetris.queue();
+        let current = tetris.player();
+
+        let mut starts = vec![(false, current, tetris.held(), 0)];
+        if tetris.can_hold() {
+            let into_hold = pieces.piece(current.brick().kind()).brick;
+            match tetris.held() {
+                Some(held) if ruleset.hold == HoldRule::Unlimited => {
+                    starts.push((true, current.set_brick(held), Some(into_hold), 0));
+                }
+                Some(held) => {
+                    starts.push((true, spawn(ruleset, board, held), Some(into_hold), 0));
+                }
+                None => {
+                    if let Some(&next) = queue.first() {
+                        starts.push((true, spawn(ruleset, board, next), Some(into_hold), 1));
+                    }
+                }
+            }
+        }
+
+        let mut beam = vec![];
+        for (hold, start, held, next) in starts {
+            for placement in placement::placements(board, start, ruleset, pieces) {
+                let first = Plan {
+                    hold,
+                    placement: placement.clone(),
+                };
+                beam.extend(self.place(board, &placement.player, held, next, 0.0, first));
+            }
+        }
+
+        for _ in 0..self.lookahead {
+            beam.sort_by(|a, b| b.value.total_cmp(&a.value));
+            beam.truncate(self.beam);
+            let children: Vec<Node> = beam
+                .iter()
+                .flat_map(|node| self.expand(node, queue, ruleset, pieces))
+                .collect();
+            if children.is_empty() {
+                break;
+            }
+            beam = children;
+        }
+
+        beam.into_iter()
+            .max_by(|a, b| a.value.total_cmp(&b.value))
+            .map(|node| node.first)
+    }
+
+    /// Every way to place the next piece of the queue after `node`, holding or not.
+    fn expand(
+        &self,
+        node: &Node,
+        queue: &[Brick],
+        ruleset: &Ruleset,
+        pieces: &PieceSet,
+    ) -> Vec<Node> {
+        let Some(&brick) = queue.get(node.next) else {
+            return vec![];
+        };
+        let mut options = vec![(brick, node.held, node.next + 1)];
+        if ruleset.hold != HoldRule::Disabled {
+            match node.held {
+                Some(held) => options.push((held, Some(brick), node.next + 1)),
+                None => {
+                    if let Some(&after) = queue.get(node.next + 1) {
+                        options.push((after, Some(brick), node.next + 2));
+                    }
+                }
+            }
+        }
+
+        let mut children = vec![];
+        for (brick, held, next) in options {
+            let start = spawn(ruleset, &node.board, brick);
+            for placement in placement::placements(&node.board, start, ruleset, pieces) {
+                children.extend(self.place(
+                    &node.board,
+                    &placement.player,
+                    held,
+                    next,
+                    node.cleared,
+                    node.first.clone(),
+                ));
+            }
+        }
+        children
+    }
+
+    /// Locks `player` into a copy of `board`, or nothing if that tops out.
+    fn place(
+        &self,
+        board: &Board,
+        player: &Player,
+        held: Option<Brick>,
+        next: usize,
+        cleared: f32,
+        first: Plan,
+    ) -> Option<Node> {
+        if player.cells().iter().all(|&(_, y)| y < 0) {
+            return None;
+        }
+        let mut board = board.clone();
+        board.insert_brick(player.position(), player.brick());
+        let cleared = cleared + self.weights.clear(board.clean_drop().len() as u32);
+        Some(Node {
+            value: cleared + self.weights.evaluate(&board),
+            board,
+            held,
+            next,
+            cleared,
+            first,
+        })
+    }
+}
+
+/// Where `brick` appears when it comes out of the queue.
+fn spawn(ruleset: &Ruleset, board: &Board, brick: Brick) -> Player {
+    ruleset.spawn.player_for(board, brick)
+}
+
+fn same_state(a: &Player, b: &Player) -> bool {
+    a.position() == b.position()
+        && a.rotation() == b.rotation()
+        && a.brick().kind() == b.brick().kind()
+}
+
+#[cfg(test)]
+mod tests {
+    use tetris_core::test_util::Counter;
+
+    use super::*;
+
+    fn play(pieces: u32) -> Tetris<Counter> {
+        let mut tetris = Tetris::with_ruleset(
+            10,
+            20,
+            Ruleset::guideline(),
+            PieceSet::tetrominoes(),
+            Counter::with_seed(7),
+        )
+        .unwrap();
+        let mut bot = Bot::default();
+        while !tetris.game_over() && tetris.pieces_locked() < pieces {
+            if let Some(action) = bot.next_action(&tetris) {
+                tetris.apply(action);
+            }
+            tetris.tick();
+            tetris.drain_events();
+        }
+        tetris
+    }
+
+    #[test]
+    fn survives_and_clears_lines() {
+        let tetris = play(60);
+        assert!(!tetris.game_over());
+        // Sixty pieces are 240 cells, more than the board holds without clearing lines.
+        assert!(tetris.lines() >= 4, "{} lines", tetris.lines());
+    }
+}
@@ -0,0 +1,219 @@
+use tetris_core::{board::Board, traits::HasSize};
+
+/// How much each feature of a board counts. Negative weights are penalties.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weights {
+    /// Sum of the column heights.
+    pub aggregate_height: f32,
+    /// Height of the tallest column.
+    pub max_height: f32,
+    /// Empty cells with a filled cell somewhere above them.
+    pub holes: f32,
+    /// Sum of the height differences between neighbouring columns.
+    pub bumpiness: f32,
+    /// Depth of the deepest well, up to four rows.
+    pub well_depth: f32,
+    /// Spots where a T piece could spin in and clear lines.
+    pub t_slots: f32,
+    /// Points for every cleared line.
+    pub lines: f32,
+    /// Extra points for clearing four lines at once.
+    pub tetris: f32,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self {
+            aggregate_height: -0.51,
+            max_height: -0.1,
+            holes: -0.8,
+            bumpiness: -0.18,
+            well_depth: 0.15,
+            t_slots: 0.3,
+            lines: 0.5,
+            tetris: 2.0,
+        }
+    }
+}
+
+impl Weights {
+    /// The value of clearing `lines` lines with one piece.
+    pub fn clear(&self, lines: u32) -> f32 {
+        let tetris = if lines >= 4 { self.tetris } else { 0.0 };
+        lines as f32 * self.lines + tetris
+    }
+
+    /// The value of the board itself, higher is better.
+    pub fn evaluate(&self, board: &Board) -> f32 {
+        let heights = heights(board);
+        let aggregate: i32 = heights.iter().sum();
+        let max = heights.iter().copied().max().unwrap_or(0);
+        let bumpiness: i32 = heights.windows(2).map(|w| (w[0] - w[1]).abs()).sum();
+
+        self.aggregate_height * aggregate as f32
+            + self.max_height * max as f32
+            + self.holes * holes(board, &heights) as f32
+            + self.bumpiness * bumpiness as f32
+            + self.well_depth * well_depth(&heights).min(4) as f32
+            + self.t_slots * t_slots(board, &heights) as f32
+    }
+}
+
+/// Height of every column, counted from the floor, buffer rows included.
+pub fn heights(board: &Board) -> Vec<i32> {
+    let (w, h) = board.size();
+    (0..w)
+        .map(|x| {
+            (-board.buffer()..h)
+                .find(|&y| board.is_taken((x, y)))
+                .map_or(0, |y| h - y)
+        })
+        .collect()
+}
+
+fn holes(board: &Board, heights: &[i32]) -> u32 {
+    let h = board.height();
+    let mut holes = 0;
+    for (x, &height) in heights.iter().enumerate() {
+        for y in h - height..h {
+            if !board.is_taken((x as i32, y)) {
+                holes += 1;
+            }
+        }
+    }
+    holes
+}
+
+/// Rows between the deepest column and the lower of its neighbours, walls count as tall.
+fn well_depth(heights: &[i32]) -> i32 {
+    (0..heights.len())
+        .map(|x| {
+            let left = if x > 0 { heights[x - 1] } else { i32::MAX };
+            let right = heights.get(x + 1).copied().unwrap_or(i32::MAX);
+            left.min(right).saturating_sub(heights[x]).max(0)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Openings shaped for a T piece pointing down, with both lower corners filled and an
+/// overhang on at least one side, right at the surface of the stack.
+fn t_slots(board: &Board, heights: &[i32]) -> u32 {
+    let h = board.height();
+    let taken = |x: i32, y: i32| board.is_taken((x, y));
+    let mut slots = 0;
+    for x in 1..board.width() - 1 {
+        // The slot's middle row, right above the lowest free cell of the column.
+        let y = h - heights[x as usize] - 2;
+        let open = !taken(x - 1, y) && !taken(x, y) && !taken(x + 1, y);
+        let floor = taken(x - 1, y + 1) && taken(x + 1, y + 1);
+        let roof = taken(x - 1, y - 1) || taken(x + 1, y - 1);
+        if open && floor && roof {
+            slots += 1;
+        }
+    }
+    slots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONE: Weights = Weights {
+        aggregate_height: 0.0,
+        max_height: 0.0,
+        holes: 0.0,
+        bumpiness: 0.0,
+        well_depth: 0.0,
+        t_slots: 0.0,
+        lines: 0.0,
+        tetris: 0.0,
+    };
+
+    fn board() -> Board {
+        Board::from_rows(&["....", "#...", "#.#.", "##.#"], 0)
+    }
+
+    #[test]
+    fn features_of_a_small_stack() {
+        let board = board();
+        let heights = heights(&board);
+        assert_eq!(heights, [3, 1, 2, 1]);
+        assert_eq!(holes(&board, &heights), 1);
+        assert_eq!(well_depth(&heights), 1);
+        assert_eq!(t_slots(&board, &heights), 0);
+    }
+
+    #[test]
+    fn every_weight_scales_its_own_feature() {
+        let board = board();
+        let only = |weights: Weights| weights.evaluate(&board);
+        assert_eq!(
+            only(Weights {
+                aggregate_height: 1.0,
+                ..NONE
+            }),
+            7.0
+        );
+        assert_eq!(
+            only(Weights {
+                max_height: 1.0,
+                ..NONE
+            }),
+            3.0
+        );
+        assert_eq!(only(Weights { holes: 1.0, ..NONE }), 1.0);
+        assert_eq!(
+            only(Weights {
+                bumpiness: 1.0,
+                ..NONE
+            }),
+            4.0
+        );
+        assert_eq!(
+            only(Weights {
+                well_depth: 1.0,
+                ..NONE
+            }),
+            1.0
+        );
+        assert_eq!(
+            only(Weights {
+                t_slots: 1.0,
+                ..NONE
+            }),
+            0.0
+        );
+
+        let expected = -0.51 * 7.0 - 0.1 * 3.0 - 0.8 - 0.18 * 4.0 + 0.15;
+        assert!((Weights::default().evaluate(&board) - expected).abs() < 1e-4);
+    }
+
+    #[test]
+    fn a_t_slot_is_found_under_an_overhang() {
+        let board = Board::from_rows(&[".#...", ".....", "##.##", "#####"], 0);
+        assert_eq!(t_slots(&board, &heights(&board)), 1);
+        let weights = Weights {
+            t_slots: 1.0,
+            ..NONE
+        };
+        assert_eq!(weights.evaluate(&board), 1.0);
+    }
+
+    #[test]
+    fn fewer_holes_and_a_lower_stack_score_higher() {
+        let weights = Weights::default();
+        let flat = Board::from_rows(&["....", "....", "....", "####"], 0);
+        let holed = Board::from_rows(&["....", "....", "####", "#.##"], 0);
+        assert!(weights.evaluate(&flat) > weights.evaluate(&board()));
+        assert!(weights.evaluate(&flat) > weights.evaluate(&holed));
+    }
+
+    #[test]
+    fn a_tetris_is_worth_more_than_its_lines() {
+        let weights = Weights::default();
+        assert_eq!(weights.clear(0), 0.0);
+        assert_eq!(weights.clear(1), 0.5);
+        assert_eq!(weights.clear(4), 4.0);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod bot;
+pub mod evaluation;
+
+pub use crate::bot::{Bot, Plan};
+pub use crate::evaluation::Weights;
@@ -0,0 +1,116 @@
+//! Collision checks on the row bitmasks of `Board` against walking the cells one by one,
+//! the way the board used to answer them.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use tetris_core::{
+    board::Board,
+    brick::Brick,
+    cell::Cell,
+    piece_set::PieceSet,
+    ruleset::Ruleset,
+    test_util::Counter,
+    tetris::Tetris,
+    traits::{HasSize, IterateDimensions},
+};
+
+fn fits_cell_by_cell(board: &Board, (ox, oy): (i32, i32), brick: Brick) -> bool {
+    let mut fits = true;
+    brick.iter_dim(|x, y, c| {
+        let at = (ox + x, oy + y);
+        if c.is_some() && (!board.contains(at) || board[at].is_some()) {
+            fits = false;
+        }
+    });
+    fits
+}
+
+fn full_cell_by_cell(board: &Board, y: i32) -> bool {
+    (0..board.width()).all(|x| board[(x, y)].is_some())
+}
+
+/// A ragged stack half way up a standard board.
+fn stack() -> Board {
+    let mut board = Board::with_buffer(10, 20, 20);
+    for y in 10..20 {
+        for x in 0..10 {
+            if (x * 7 + y * 3) % 10 != 0 {
+                board.set_field((x, y), Some(Cell::Garbage));
+            }
+        }
+    }
+    board
+}
+
+/// Where each piece lands dropped straight down from every column, the search behind the
+/// ghost piece and hard drops.
+fn drops(
+    board: &Board,
+    pieces: &PieceSet,
+    fits: impl Fn(&Board, (i32, i32), Brick) -> bool,
+) -> i32 {
+    let mut total = 0;
+    for piece in pieces.pieces() {
+        for x in -2..board.width() {
+            let mut y = -2;
+            if !fits(board, (x, y), piece.brick) {
+                continue;
+            }
+            while fits(board, (x, y + 1), piece.brick) {
+                y += 1;
+            }
+            total += y;
+        }
+    }
+    total
+}
+
+fn collisions(c: &mut Criterion) {
+    let board = stack();
+    let pieces = PieceSet::tetrominoes();
+    let mut group = c.benchmark_group("drops");
+    group.bench_function("cells", |b| {
+        b.iter(|| drops(black_box(&board), &pieces, fits_cell_by_cell))
+    });
+    group.bench_function("bitboard", |b| {
+        b.iter(|| {
+            drops(black_box(&board), &pieces, |board, at, brick| {
+                board.brick_fits(at, brick)
+            })
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("full_rows");
+    group.bench_function("cells", |b| {
+        b.iter(|| {
+            let board = black_box(&board);
+            (-board.buffer()..board.height())
+                .filter(|&y| full_cell_by_cell(board, y))
+                .count()
+        })
+    });
+    group.bench_function("bitboard", |b| {
+        b.iter(|| {
+            let board = black_box(&board);
+            (-board.buffer()..board.height())
+                .filter(|&y| board.line_full(y))
+                .count()
+        })
+    });
+    group.finish();
+}
+
+fn placements(c: &mut Criterion) {
+    let tetris = Tetris::with_ruleset(
+        10,
+        20,
+        Ruleset::guideline(),
+        PieceSet::tetrominoes(),
+        Counter::default(),
+    )
+    .expect("a standard board");
+    c.bench_function("placements", |b| b.iter(|| black_box(&tetris).placements()));
+}
+
+criterion_group!(benches, collisions, placements);
+criterion_main!(benches);
@@ -0,0 +1,141 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use tetris_core::{
+    action::Action, board::Board, brick::Brick, cell::Cell, piece_set::PieceSet, ruleset::Ruleset,
+    tetris::Tetris, traits::Randomizer,
+};
+
+const ACTIONS: [Action; 9] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::ShiftLeft,
+    Action::ShiftRight,
+    Action::SoftDrop,
+    Action::HardDrop,
+    Action::RotateLeft,
+    Action::RotateRight,
+    Action::Hold,
+];
+
+/// A small linear congruential generator, so a crash plays out the same way again.
+#[derive(Debug, Clone, Default)]
+struct Lcg(i32);
+
+impl Randomizer for Lcg {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_seed(seed: i32) -> Self {
+        Self(seed)
+    }
+
+    fn next(&mut self) -> i32 {
+        self.0 = self.0.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        self.0
+    }
+}
+
+#[derive(Debug, Arbitrary)]
+enum Step {
+    Apply(u8),
+    Tick(u8),
+    Garbage(u8),
+    /// Pokes the board of the game with coordinates that may be anywhere.
+    Board(Probe),
+}
+
+#[derive(Debug, Arbitrary)]
+enum Probe {
+    Set { x: i32, y: i32, filled: bool },
+    Taken { x: i32, y: i32 },
+    Fits { x: i32, y: i32, kind: u8 },
+    Row(i32),
+    Clear(i32),
+    Drop(i32),
+    Garbage(i32),
+    CleanDrop,
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    seed: i32,
+    preset: u8,
+    pieces: u8,
+    width: i16,
+    height: i16,
+    steps: Vec<Step>,
+}
+
+fn probe(board: &mut Board, probe: &Probe) {
+    match *probe {
+        Probe::Set { x, y, filled } => {
+            if let Some(mut cell) = board.get_mut((x, y)) {
+                *cell = filled.then_some(Cell::Garbage);
+            }
+            assert_eq!(board.is_taken((x, y)), filled || !board.contains((x, y)));
+        }
+        Probe::Taken { x, y } => {
+            assert_eq!(board.get((x, y)).is_some(), board.contains((x, y)));
+            board.is_taken((x, y));
+        }
+        Probe::Fits { x, y, kind } => {
+            board.brick_fits((x, y), Brick::by_index(kind as i32));
+        }
+        Probe::Row(y) => {
+            board.row(y);
+            board.line_full(y);
+        }
+        Probe::Clear(y) => board.clear_line(y),
+        Probe::Drop(y) => board.drop_line(y),
+        Probe::Garbage(hole) => {
+            board.push_garbage(hole);
+        }
+        Probe::CleanDrop => {
+            board.clean_drop();
+        }
+    }
+}
+
+fuzz_target!(|input: Input| {
+    let presets = Ruleset::PRESETS;
+    let ruleset =
+        Ruleset::preset(presets[input.preset as usize % presets.len()]).expect("presets load");
+    let pieces = match input.pieces % 4 {
+        0 => PieceSet::tetrominoes(),
+        1 => PieceSet::pentominoes(),
+        2 => PieceSet::trominoes(),
+        _ => PieceSet::tetrominoes().scaled(2),
+    };
+    let (w, h) = (input.width as i32, input.height as i32);
+    // Sizes the rules turn down are not games, turning them down must not panic though.
+    let seed = Lcg::with_seed(input.seed);
+    let Ok(mut tetris) = Tetris::with_ruleset(w, h, ruleset, pieces, seed) else {
+        return;
+    };
+    for step in &input.steps {
+        match *step {
+            Step::Apply(action) => {
+                tetris.apply(ACTIONS[action as usize % ACTIONS.len()]);
+            }
+            Step::Tick(frames) => {
+                for _ in 0..frames {
+                    tetris.tick();
+                }
+            }
+            Step::Garbage(rows) => {
+                // A piece the garbage leaves stuck tops the game out on the next frame.
+                tetris.add_garbage(rows as u32 % 8);
+                tetris.tick();
+            }
+            Step::Board(ref p) => probe(&mut tetris.board().clone(), p),
+        }
+        tetris.drain_events();
+        if !tetris.game_over() && !tetris.spawning() {
+            assert!(tetris.player().brick_fits(tetris.board()));
+        }
+    }
+    tetris.placements();
+});
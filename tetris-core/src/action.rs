@@ -0,0 +1,46 @@
+/// Everything a player can do to the piece in play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    /// Move left until hitting something.
+    ShiftLeft,
+    ShiftRight,
+    SoftDrop,
+    HardDrop,
+    RotateLeft,
+    RotateRight,
+    Hold,
+}
+
+/// Actions tagged with the simulation frame they were applied on, before that frame's tick.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Replay {
+    pub seed: i32,
+    pub inputs: Vec<(u32, Action)>,
+}
+
+impl Replay {
+    pub fn new(seed: i32) -> Self {
+        Self {
+            seed,
+            inputs: vec![],
+        }
+    }
+
+    pub fn record(&mut self, frame: u32, action: Action) {
+        self.inputs.push((frame, action));
+    }
+
+    /// The actions applied on `frame`, in the order they were recorded.
+    pub fn actions_at(&self, frame: u32) -> impl Iterator<Item = Action> + '_ {
+        let start = self.inputs.partition_point(|&(f, _)| f < frame);
+        self.inputs[start..]
+            .iter()
+            .take_while(move |&&(f, _)| f == frame)
+            .map(|&(_, action)| action)
+    }
+}
@@ -1,30 +1,151 @@
 use std::{
     fmt::Display,
-    ops::{Index, IndexMut},
+    ops::{Deref, DerefMut, Index},
 };
 
 use crate::{
     brick::Brick,
     cell::Cell,
+    error::TetrisError,
     traits::{HasSize, IterateDimensions},
 };
 
 pub const WIDTH: u32 = 10;
 pub const HEIGHT: u32 = 20;
+/// Largest board a game can be played on, buffer rows not counted.
+pub const MAX_WIDTH: u32 = 128;
+pub const MAX_HEIGHT: u32 = 128;
 
+/// The well. Rows `0..height` are visible, the `buffer` rows above them have negative `y`
+/// and take part in collisions and clears without being drawn.
+///
+/// Next to the look of every cell each row is kept as a bitmask of the taken cells, so
+/// collisions and full rows are checked a row at a time.
 #[derive(Debug, Clone)]
 pub struct Board {
     size: (i32, i32),
+    buffer: i32,
     cells: Vec<Option<Cell>>,
+    /// Bit `x` of each row is set when the cell is taken, buffer rows first.
+    rows: Vec<u128>,
 }
 
 impl Board {
+    /// An empty board of `w` by `h` cells without buffer rows.
+    ///
+    /// # Panics
+    ///
+    /// If [`Board::check_size`] turns the size down, see [`Board::try_new`].
     pub fn new(w: i32, h: i32) -> Self {
-        Self {
+        Self::with_buffer(w, h, 0)
+    }
+
+    /// An empty board of `w` by `h` cells without buffer rows, or the reason the size is
+    /// refused.
+    ///
+    /// # Errors
+    ///
+    /// The [`TetrisError`] [`Board::check_size`] gives for the size.
+    pub fn try_new(w: i32, h: i32) -> Result<Self, TetrisError> {
+        Self::try_with_buffer(w, h, 0)
+    }
+
+    /// An empty board of `w` by `h` cells with `buffer` hidden rows above.
+    ///
+    /// # Panics
+    ///
+    /// If [`Board::check_size`] turns the size down, see [`Board::try_with_buffer`].
+    pub fn with_buffer(w: i32, h: i32, buffer: i32) -> Self {
+        Self::try_with_buffer(w, h, buffer).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// An empty board of `w` by `h` cells with `buffer` hidden rows above, or the reason the
+    /// size is refused.
+    ///
+    /// # Errors
+    ///
+    /// The [`TetrisError`] [`Board::check_size`] gives for the size.
+    pub fn try_with_buffer(w: i32, h: i32, buffer: i32) -> Result<Self, TetrisError> {
+        Self::check_size(w, h, buffer)?;
+        Ok(Self {
             size: (w, h),
-            cells: vec![None; w as usize * h as usize],
+            buffer,
+            cells: vec![None; w as usize * (h + buffer) as usize],
+            rows: vec![0; (h + buffer) as usize],
+        })
+    }
+
+    /// Checks that a board of `w` by `h` visible cells with `buffer` rows above can be made.
+    pub fn check_size(w: i32, h: i32, buffer: i32) -> Result<(), TetrisError> {
+        if !(1..=MAX_WIDTH as i32).contains(&w) {
+            return Err(TetrisError::InvalidWidth(w));
+        }
+        if !(1..=MAX_HEIGHT as i32).contains(&h) {
+            return Err(TetrisError::InvalidHeight(h));
+        }
+        if buffer < 0 {
+            return Err(TetrisError::InvalidBuffer(buffer));
         }
+        Ok(())
     }
+
+    /// A board laid out row by row from the top, `#` for a filled cell and anything else for
+    /// an empty one. Filled cells are garbage. Rows shorter than the first are padded.
+    pub fn from_rows(rows: &[&str], buffer: i32) -> Self {
+        let width = rows.first().map_or(0, |r| r.chars().count()) as i32;
+        let mut board = Self::with_buffer(width, rows.len() as i32, buffer);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate().take(width as usize) {
+                if c == '#' {
+                    board.set_field((x as i32, y as i32), Some(Cell::Garbage));
+                }
+            }
+        }
+        board
+    }
+
+    /// Whether no cell is filled, buffer rows included.
+    pub fn is_empty(&self) -> bool {
+        self.rows.iter().all(|&row| row == 0)
+    }
+
+    /// The cell at `(x, y)`, `None` outside the board.
+    pub fn get(&self, (x, y): (i32, i32)) -> Option<&Option<Cell>> {
+        self.contains((x, y))
+            .then(|| &self.cells[self.cell_index((x, y))])
+    }
+
+    /// The cell at `(x, y)` to change in place, `None` outside the board. The row bitmask
+    /// follows the change once the returned handle is dropped.
+    pub fn get_mut(&mut self, (x, y): (i32, i32)) -> Option<CellMut<'_>> {
+        if !self.contains((x, y)) {
+            return None;
+        }
+        let index = self.cell_index((x, y));
+        Some(CellMut {
+            cell: &mut self.cells[index],
+            row: &mut self.rows[(y + self.buffer) as usize],
+            bit: 1 << x,
+        })
+    }
+
+    /// Position of `(x, y)` in `cells`.
+    fn cell_index(&self, (x, y): (i32, i32)) -> usize {
+        ((y + self.buffer) * self.width() + x) as usize
+    }
+
+    /// Hidden rows above the visible ones.
+    pub fn buffer(&self) -> i32 {
+        self.buffer
+    }
+
+    /// Whether `(x, y)` is inside the board, buffer rows included.
+    pub fn contains(&self, (x, y): (i32, i32)) -> bool {
+        x >= 0 && x < self.width() && y >= -self.buffer && y < self.height()
+    }
+
+    /// Fills the cells of `brick` with its box at `(ox, oy)`, over whatever is there. Cells
+    /// outside the board are left out.
     pub fn insert_brick(&mut self, (ox, oy): (i32, i32), brick: Brick) {
         brick.iter_dim(|x, y, c| {
             if let Some(c) = c {
@@ -33,79 +154,168 @@ impl Board {
         });
     }
 
+    /// Like [`Board::insert_brick`], but only where the brick fits, leaving the board as it
+    /// was otherwise.
+    pub fn try_insert_brick(
+        &mut self,
+        position: (i32, i32),
+        brick: Brick,
+    ) -> Result<(), TetrisError> {
+        if !self.brick_fits(position, brick) {
+            return Err(TetrisError::InvalidPlacement { position });
+        }
+        self.insert_brick(position, brick);
+        Ok(())
+    }
+
     pub fn set_field(&mut self, (x, y): (i32, i32), state: Option<Cell>) {
-        if x >= 0 && x < self.width() && y >= 0 && y < self.height() {
-            self[(x, y)] = state;
+        if self.contains((x, y)) {
+            let index = self.cell_index((x, y));
+            self.cells[index] = state;
+            let row = &mut self.rows[(y + self.buffer) as usize];
+            if state.is_some() {
+                *row |= 1 << x;
+            } else {
+                *row &= !(1 << x);
+            }
         }
     }
 
+    #[inline]
     pub fn is_taken(&self, (x, y): (i32, i32)) -> bool {
-        if x >= 0 && x < self.width() && y >= 0 && y < self.height() {
-            self[(x, y)].is_some()
+        if self.contains((x, y)) {
+            self.row(y) & (1 << x) != 0
         } else {
             true
         }
     }
 
-    pub fn brick_fits(&self, (ox, oy): (i32, i32), brick: Brick) -> bool {
-        let mut res = true;
-        brick.iter_dim(|x, y, c| {
-            if c.is_some() && self.is_taken((x + ox, y + oy)) {
-                res = false;
-            }
-        });
-        res
+    /// Row `y` as a bitmask, bit `x` set for every taken cell. Rows outside the board are
+    /// empty.
+    #[inline]
+    pub fn row(&self, y: i32) -> u128 {
+        let index = y + self.buffer;
+        if (0..self.rows.len() as i32).contains(&index) {
+            self.rows[index as usize]
+        } else {
+            0
+        }
     }
 
-    pub fn line_full(&self, y: i32) -> bool {
-        if y > self.height() - 1 {
-            return false;
-        }
-        for x in 0..self.width() {
-            if !self.is_taken((x, y)) {
+    /// Bitmask of a row with every cell taken.
+    #[inline]
+    fn full_row(&self) -> u128 {
+        u128::MAX
+            .checked_shr(u128::BITS - self.width() as u32)
+            .unwrap_or(0)
+    }
+
+    #[inline]
+    pub fn brick_fits(&self, (ox, oy): (i32, i32), brick: Brick) -> bool {
+        for (dy, bits) in brick.rows().into_iter().enumerate() {
+            if bits == 0 {
+                continue;
+            }
+            // Above the buffer the index wraps around and is out of range as well.
+            let Some(&row) = self.rows.get((oy + self.buffer + dy as i32) as usize) else {
+                return false;
+            };
+            // Cells past either wall do not fit, which also keeps the shift below in range.
+            let left = ox + bits.trailing_zeros() as i32;
+            let right = ox + 7 - bits.leading_zeros() as i32;
+            if left < 0 || right >= self.size.0 {
+                return false;
+            }
+            let bits = bits as u128;
+            let shifted = if ox >= 0 { bits << ox } else { bits >> -ox };
+            if row & shifted != 0 {
                 return false;
             }
         }
         true
     }
 
+    #[inline]
+    pub fn line_full(&self, y: i32) -> bool {
+        y < self.height() && self.row(y) == self.full_row()
+    }
+
+    pub fn full_lines(&self) -> Vec<i32> {
+        (-self.buffer..self.height())
+            .filter(|&y| self.line_full(y))
+            .collect()
+    }
+
     pub fn clear_line(&mut self, y: i32) {
-        for x in 0..self.width() {
-            self.set_field((x, y), None);
+        if self.contains((0, y)) {
+            let start = self.cell_index((0, y));
+            let end = start + self.width() as usize;
+            self.cells[start..end].fill(None);
+            self.rows[(y + self.buffer) as usize] = 0;
         }
     }
 
+    /// Moves row `y` down onto the row below it, leaving it empty.
     pub fn drop_line(&mut self, y: i32) {
-        if y < self.height() - 1 {
-            for x in 0..self.width() {
-                let tmp = self[(x, y)];
-                self[(x, y)] = None;
-                self[(x, y + 1)] = tmp;
-            }
+        if self.contains((0, y)) && y < self.height() - 1 {
+            let w = self.width() as usize;
+            let start = self.cell_index((0, y));
+            self.cells.copy_within(start..start + w, start + w);
+            self.cells[start..start + w].fill(None);
+            let row = (y + self.buffer) as usize;
+            self.rows[row + 1] = self.rows[row];
+            self.rows[row] = 0;
         }
     }
 
-    pub fn clean_drop(&mut self) -> u32 {
-        let mut cleared = 0;
-        for y in (0..self.height()).rev() {
-            if self.line_full(y) {
-                cleared += 1;
-                self.clear_line(y);
-                for dy in (0..y).rev() {
-                    self.drop_line(dy);
-                }
+    /// Pushes every row up by one and fills the bottom row except for `hole`. Returns false
+    /// if cells were pushed out of the top.
+    pub fn push_garbage(&mut self, hole: i32) -> bool {
+        let (w, h) = self.size();
+        let fits = self.rows[0] == 0;
+        self.cells.rotate_left(w as usize);
+        self.rows.rotate_left(1);
+        let bottom = self.cell_index((0, h - 1));
+        for x in 0..w {
+            self.cells[bottom + x as usize] = (x != hole).then_some(Cell::Garbage);
+        }
+        let hole_bit = if (0..w).contains(&hole) { 1 << hole } else { 0 };
+        *self.rows.last_mut().expect("boards have rows") = self.full_row() & !hole_bit;
+        fits
+    }
+
+    /// Removes every full row and lets the rows above fall into their place, in a single
+    /// pass from the bottom up. Returns the rows that were full, top down, numbered as they
+    /// were before the clear.
+    pub fn clean_drop(&mut self) -> Vec<i32> {
+        let w = self.width() as usize;
+        let mut cleared = vec![];
+        // The lowest row not yet filled by a row kept from below.
+        let mut to = self.height() - 1;
+        for from in (-self.buffer..self.height()).rev() {
+            if self.line_full(from) {
+                cleared.push(from);
+                continue;
             }
+            if to != from {
+                let (src, dst) = (self.cell_index((0, from)), self.cell_index((0, to)));
+                self.cells.copy_within(src..src + w, dst);
+                self.rows[(to + self.buffer) as usize] = self.rows[(from + self.buffer) as usize];
+            }
+            to -= 1;
         }
+        // As many rows as were cleared come in empty at the top.
+        let empty = (to + 1 + self.buffer) as usize;
+        self.cells[..empty * w].fill(None);
+        self.rows[..empty].fill(0);
+        cleared.reverse();
         cleared
     }
 }
 
 impl Default for Board {
     fn default() -> Self {
-        Self {
-            size: (WIDTH as i32, HEIGHT as i32),
-            cells: vec![None; WIDTH as usize * HEIGHT as usize],
-        }
+        Self::new(WIDTH as i32, HEIGHT as i32)
     }
 }
 
@@ -147,15 +357,49 @@ impl IterateDimensions for Board {
 impl Index<(i32, i32)> for Board {
     type Output = Option<Cell>;
 
-    fn index(&self, (x, y): (i32, i32)) -> &Self::Output {
-        let idx = y * self.width() + x;
-        &self.cells[idx as usize]
+    /// # Panics
+    ///
+    /// If `position` is outside the board, see [`Board::get`].
+    fn index(&self, position: (i32, i32)) -> &Self::Output {
+        self.get(position).unwrap_or_else(|| {
+            panic!(
+                "{position:?} is outside the board of {}x{} with {} buffer rows",
+                self.width(),
+                self.height(),
+                self.buffer
+            )
+        })
+    }
+}
+
+/// A cell of a [`Board`] borrowed with [`Board::get_mut`].
+#[derive(Debug)]
+pub struct CellMut<'a> {
+    cell: &'a mut Option<Cell>,
+    row: &'a mut u128,
+    bit: u128,
+}
+
+impl Deref for CellMut<'_> {
+    type Target = Option<Cell>;
+
+    fn deref(&self) -> &Self::Target {
+        self.cell
     }
 }
 
-impl IndexMut<(i32, i32)> for Board {
-    fn index_mut(&mut self, (x, y): (i32, i32)) -> &mut Self::Output {
-        let idx = y * self.width() + x;
-        &mut self.cells[idx as usize]
+impl DerefMut for CellMut<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.cell
+    }
+}
+
+impl Drop for CellMut<'_> {
+    fn drop(&mut self) {
+        if self.cell.is_some() {
+            *self.row |= self.bit;
+        } else {
+            *self.row &= !self.bit;
+        }
     }
 }
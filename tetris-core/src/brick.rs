@@ -1,87 +1,100 @@
-use std::ops::{Index, IndexMut};
-
 use crate::{
     cell::Cell,
-    color::Color,
+    palette::Palette,
     traits::{HasSize, IterateDimensions},
 };
 
-#[derive(Debug, Default, Clone, Copy)]
-pub struct Brick([Option<Cell>; 4 * 4]);
+/// Largest side of the square box a brick is defined in.
+pub const MAX_SIZE: i32 = 8;
+
+/// A piece shape in a square box of up to `MAX_SIZE` cells a side, all cells sharing one look.
+#[derive(Debug, Clone, Copy)]
+pub struct Brick {
+    size: i32,
+    /// Bit `y * MAX_SIZE + x` is set for every occupied cell.
+    mask: u64,
+    cell: Cell,
+    /// Index of the piece in the piece set it came from.
+    kind: usize,
+}
 
-const RED: Option<Cell> = Some(Cell::Normal(Color(255, 0, 0)));
-const GREEN: Option<Cell> = Some(Cell::Normal(Color(0, 255, 0)));
-const BLUE: Option<Cell> = Some(Cell::Normal(Color(0, 0, 255)));
-const ORANGE: Option<Cell> = Some(Cell::Normal(Color(255, 165, 0)));
-const YELLOW: Option<Cell> = Some(Cell::Normal(Color(255, 255, 0)));
-const PURPLE: Option<Cell> = Some(Cell::Normal(Color(255, 0, 255)));
-const CYAN: Option<Cell> = Some(Cell::Normal(Color(0, 255, 255)));
+impl Default for Brick {
+    fn default() -> Self {
+        Self {
+            size: 4,
+            mask: 0,
+            cell: Cell::Ghost,
+            kind: 0,
+        }
+    }
+}
+
+const fn piece_cell(i: usize) -> Cell {
+    Cell::Normal {
+        kind: i,
+        color: Palette::GUIDELINE.pieces[i],
+    }
+}
 
 #[rustfmt::skip]
 impl Brick {
     pub fn z() -> Self {
-        Self([
-            None, None, None, None,
-             RED,  RED, None, None,
-            None,  RED,  RED, None,
-            None, None, None, None,
-        ])
+        Self::from_rows(6, &[
+            "##.",
+            ".##",
+            "...",
+        ], piece_cell(6))
     }
 
     pub fn s() -> Self {
-        Self([
-            None,  None,  None,  None,
-            None,  None, GREEN, GREEN,
-            None, GREEN, GREEN,  None,
-            None,  None,  None,  None,
-        ])
+        Self::from_rows(5, &[
+            ".##",
+            "##.",
+            "...",
+        ], piece_cell(5))
     }
 
     pub fn l() -> Self {
-        Self([
-            None, ORANGE,   None, None,
-            None, ORANGE,   None, None,
-            None, ORANGE, ORANGE, None,
-            None,   None,   None, None,
-        ])
+        Self::from_rows(4, &[
+            "..#",
+            "###",
+            "...",
+        ], piece_cell(4))
     }
 
     pub fn j() -> Self {
-        Self([
-            None, None, BLUE, None,
-            None, None, BLUE, None,
-            None, BLUE, BLUE, None,
-            None, None, None, None,
-        ])
+        Self::from_rows(3, &[
+            "#..",
+            "###",
+            "...",
+        ], piece_cell(3))
     }
 
     pub fn t() -> Self {
-        Self([
-              None,   None,   None, None,
-            PURPLE, PURPLE, PURPLE, None,
-              None, PURPLE,   None, None,
-              None,   None,   None, None,
-        ])
+        Self::from_rows(2, &[
+            ".#.",
+            "###",
+            "...",
+        ], piece_cell(2))
     }
 
     pub fn o() -> Self {
-        Self([
-            None,   None,   None, None,
-            None, YELLOW, YELLOW, None,
-            None, YELLOW, YELLOW, None,
-            None,   None,   None, None,
-        ])
+        Self::from_rows(1, &[
+            "##",
+            "##",
+        ], piece_cell(1))
     }
 
     pub fn i() -> Self {
-        Self([
-            None, None, None, None,
-            CYAN, CYAN, CYAN, CYAN,
-            None, None, None, None,
-            None, None, None, None,
-        ])
+        Self::from_rows(0, &[
+            "....",
+            "####",
+            "....",
+            "....",
+        ], piece_cell(0))
     }
 
+    /// One of the seven tetrominoes in I, O, T, J, L, S, Z order.
     pub fn by_index(i: i32) -> Self {
         let i = i.abs().wrapping_rem(7);
         match i {
@@ -98,26 +111,116 @@ impl Brick {
 }
 
 impl Brick {
+    /// Builds a brick from rows of text where `.` and ` ` are empty and anything else is
+    /// occupied. The box is square, sized after the longer of the row count and row length.
+    pub fn from_rows(kind: usize, rows: &[&str], cell: Cell) -> Self {
+        let size = rows
+            .iter()
+            .map(|r| r.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(rows.len()) as i32;
+        assert!(
+            size <= MAX_SIZE,
+            "bricks can be at most {MAX_SIZE} cells wide"
+        );
+
+        let mut mask = 0;
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c != '.' && c != ' ' {
+                    mask |= Self::bit(x as i32, y as i32);
+                }
+            }
+        }
+        Self {
+            size,
+            mask,
+            cell,
+            kind,
+        }
+    }
+
+    fn bit(x: i32, y: i32) -> u64 {
+        1 << (y * MAX_SIZE + x)
+    }
+
+    pub fn is_set(&self, x: i32, y: i32) -> bool {
+        x >= 0 && x < self.size && y >= 0 && y < self.size && self.mask & Self::bit(x, y) != 0
+    }
+
+    /// The rows of the box from the top, bit `x` set for every occupied cell. Rows past the
+    /// size of the box are empty.
+    #[inline]
+    pub fn rows(&self) -> [u8; MAX_SIZE as usize] {
+        self.mask.to_le_bytes()
+    }
+
+    pub fn kind(&self) -> usize {
+        self.kind
+    }
+
+    /// Same brick as piece `kind`, its cells included.
+    pub fn with_kind(&self, kind: usize) -> Self {
+        let cell = match self.cell {
+            Cell::Normal { color, .. } => Cell::Normal { kind, color },
+            cell => cell,
+        };
+        Self {
+            kind,
+            cell,
+            ..*self
+        }
+    }
+
+    pub fn cell(&self) -> Cell {
+        self.cell
+    }
+
+    /// Number of occupied cells.
+    pub fn count(&self) -> u32 {
+        self.mask.count_ones()
+    }
+
     pub fn as_ghost(&self) -> Self {
-        let mut new = Self::default();
-        for y in 0..4 {
-            for x in 0..4 {
-                if self.get_item(x, y).is_some() {
-                    new[(x, y)] = Some(Cell::Ghost);
+        Self {
+            cell: Cell::Ghost,
+            ..*self
+        }
+    }
+
+    /// Same shape with every cell blown up to a `factor` by `factor` square, for big modes.
+    pub fn scaled(&self, factor: i32) -> Self {
+        let size = self.size * factor;
+        assert!(
+            size <= MAX_SIZE,
+            "bricks can be at most {MAX_SIZE} cells wide"
+        );
+        let mut mask = 0;
+        for y in 0..size {
+            for x in 0..size {
+                if self.is_set(x / factor, y / factor) {
+                    mask |= Self::bit(x, y);
                 }
             }
         }
-        new
+        Self {
+            size,
+            mask,
+            ..*self
+        }
     }
 
     pub fn rotate_left(&self) -> Self {
-        let mut new = Self([None; 4 * 4]);
-        for i in 0..4 {
-            for j in 0..4 {
-                new[(i, j)] = self[(4 - j - 1, i)];
+        let mut mask = 0;
+        for y in 0..self.size {
+            for x in 0..self.size {
+                if self.is_set(self.size - y - 1, x) {
+                    mask |= Self::bit(x, y);
+                }
             }
         }
-        new
+        Self { mask, ..*self }
     }
 
     pub fn rotate_right(&self) -> Self {
@@ -125,28 +228,13 @@ impl Brick {
     }
 }
 
-impl Index<(i32, i32)> for Brick {
-    type Output = Option<Cell>;
-    fn index(&self, (x, y): (i32, i32)) -> &Self::Output {
-        let idx = y * 4 + x;
-        &self.0[idx as usize]
-    }
-}
-
-impl IndexMut<(i32, i32)> for Brick {
-    fn index_mut(&mut self, (x, y): (i32, i32)) -> &mut Self::Output {
-        let idx = y * 4 + x;
-        &mut self.0[idx as usize]
-    }
-}
-
 impl HasSize for Brick {
     fn width(&self) -> i32 {
-        4
+        self.size
     }
 
     fn height(&self) -> i32 {
-        4
+        self.size
     }
 }
 
@@ -154,7 +242,6 @@ impl IterateDimensions for Brick {
     type Output = Option<Cell>;
 
     fn get_item(&self, x: i32, y: i32) -> Self::Output {
-        let idx = y * self.width() + x;
-        self.0[idx as usize]
+        self.is_set(x, y).then_some(self.cell)
     }
 }
@@ -2,6 +2,13 @@ use crate::color::Color;
 
 #[derive(Debug, Clone, Copy)]
 pub enum Cell {
-    Normal(Color),
+    /// A cell of piece `kind`, its position in the piece set, drawn in `color` unless a
+    /// frontend has its own colours for the kinds.
+    Normal {
+        kind: usize,
+        color: Color,
+    },
     Ghost,
+    /// Rows pushed up from below the stack.
+    Garbage,
 }
@@ -0,0 +1,150 @@
+use std::fmt::Display;
+
+use crate::board::{MAX_HEIGHT, MAX_WIDTH};
+
+/// Why a board or a game could not be set up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TetrisError {
+    /// A board narrower than one column or wider than [`MAX_WIDTH`].
+    InvalidWidth(i32),
+    /// A board lower than one row or higher than [`MAX_HEIGHT`], buffer rows not counted.
+    InvalidHeight(i32),
+    /// A negative number of buffer rows.
+    InvalidBuffer(i32),
+    /// A piece that does not fit the board in every orientation.
+    PieceTooLarge {
+        piece: String,
+        size: i32,
+        width: i32,
+        height: i32,
+    },
+    /// A piece that would spawn partly outside the board, buffer rows included. `position`
+    /// is the top left corner of its box of `size` cells.
+    SpawnOutside {
+        piece: String,
+        position: (i32, i32),
+        size: i32,
+        width: i32,
+        height: i32,
+        buffer: i32,
+    },
+    /// A brick placed over filled cells or partly outside the board. `position` is the top
+    /// left corner of its box.
+    InvalidPlacement { position: (i32, i32) },
+}
+
+impl Display for TetrisError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TetrisError::InvalidWidth(width) => write!(
+                f,
+                "the board is {width} columns wide, it can have 1 to {MAX_WIDTH}"
+            ),
+            TetrisError::InvalidHeight(height) => write!(
+                f,
+                "the board is {height} rows high, it can have 1 to {MAX_HEIGHT}"
+            ),
+            TetrisError::InvalidBuffer(buffer) => {
+                write!(
+                    f,
+                    "the board has {buffer} buffer rows, it cannot have less than 0"
+                )
+            }
+            TetrisError::PieceTooLarge {
+                piece,
+                size,
+                width,
+                height,
+            } => {
+                if size > width {
+                    write!(
+                        f,
+                        "the {piece} piece needs {size} columns, the board has {width}"
+                    )
+                } else {
+                    write!(
+                        f,
+                        "the {piece} piece needs {size} rows, the board has {height}"
+                    )
+                }
+            }
+            TetrisError::SpawnOutside {
+                piece,
+                position: (column, row),
+                size,
+                width,
+                height,
+                buffer,
+            } => {
+                if *column < 0 || column + size > *width {
+                    write!(
+                        f,
+                        "the {piece} piece would spawn at column {column}, outside a board \
+                         {width} wide"
+                    )
+                } else {
+                    write!(
+                        f,
+                        "the {piece} piece would spawn at row {row}, outside a board {height} \
+                         high with {buffer} buffer rows"
+                    )
+                }
+            }
+            TetrisError::InvalidPlacement {
+                position: (column, row),
+            } => write!(
+                f,
+                "a piece does not fit at column {column}, row {row} of the board"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TetrisError {}
+
+/// Why a puzzle could not be started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// A board without any columns.
+    NoColumns,
+    /// A row of the board not as wide as the first.
+    UnevenRow {
+        row: String,
+        width: usize,
+    },
+    /// A character in the board other than `#` and `.`.
+    UnexpectedCell(char),
+    /// A piece name that is not one of the tetrominoes.
+    UnknownPiece(String),
+    NoPieces,
+    /// The board or pieces do not make a game under the puzzle's rules.
+    Game(TetrisError),
+}
+
+impl From<TetrisError> for PuzzleError {
+    fn from(error: TetrisError) -> Self {
+        Self::Game(error)
+    }
+}
+
+impl Display for PuzzleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PuzzleError::NoColumns => write!(f, "the board has no columns"),
+            PuzzleError::UnevenRow { row, width } => write!(f, "row {row:?} is not {width} wide"),
+            PuzzleError::UnexpectedCell(c) => write!(f, "unexpected {c:?} in the board"),
+            PuzzleError::UnknownPiece(name) => write!(f, "unknown piece {name:?}"),
+            PuzzleError::NoPieces => write!(f, "no pieces to play"),
+            PuzzleError::Game(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for PuzzleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PuzzleError::Game(error) => Some(error),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,24 @@
+/// Something that happened inside [`Tetris`](crate::tetris::Tetris) the frontend may want to react to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// A piece was locked into the board, covering these cells.
+    Locked(Vec<(i32, i32)>),
+    /// The active piece fell `distance` rows straight down before locking at `cells`.
+    HardDropped {
+        cells: Vec<(i32, i32)>,
+        distance: i32,
+    },
+    /// These rows were full and have been removed from the board. `combo` counts the
+    /// clears made by the pieces locked just before this one without a break.
+    LinesCleared {
+        rows: Vec<i32>,
+        combo: u32,
+    },
+    /// A T piece locked right after rotating, with three of the corners around its centre
+    /// blocked. `lines` is how many lines it completed.
+    TSpin {
+        lines: u32,
+    },
+    LevelUp(u32),
+    GameOver,
+}
@@ -0,0 +1,51 @@
+use crate::{ruleset::Generator, traits::Randomizer};
+
+/// Picks piece indices according to a `Generator`, keeping the bag or history between picks.
+#[derive(Debug, Clone, Default)]
+pub struct PieceGenerator {
+    generator: Generator,
+    bag: Vec<usize>,
+    history: Vec<usize>,
+}
+
+impl PieceGenerator {
+    pub fn new(generator: Generator) -> Self {
+        Self {
+            generator,
+            ..Default::default()
+        }
+    }
+
+    /// Index of the next piece out of `count` pieces.
+    pub fn next(&mut self, count: usize, rng: &mut impl Randomizer) -> usize {
+        let mut roll = |bound: usize| rng.next().rem_euclid(bound as i32) as usize;
+        match self.generator {
+            Generator::Random => roll(count),
+            Generator::Bag => {
+                if self.bag.is_empty() {
+                    self.bag = (0..count).collect();
+                    // Fisher-Yates, drawing from the back.
+                    for i in (1..count).rev() {
+                        let j = roll(i + 1);
+                        self.bag.swap(i, j);
+                    }
+                }
+                self.bag.pop().unwrap_or(0)
+            }
+            Generator::History { size, rolls } => {
+                let mut piece = roll(count);
+                for _ in 1..rolls {
+                    if !self.history.contains(&piece) {
+                        break;
+                    }
+                    piece = roll(count);
+                }
+                self.history.push(piece);
+                if self.history.len() > size {
+                    self.history.remove(0);
+                }
+                piece
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::VecDeque;
+
+use crate::{tetris::Tetris, traits::Randomizer};
+
+/// Snapshots to step back and forth through, e.g. one per piece placed. The newest
+/// snapshot is the present, older ones are dropped past `limit`.
+#[derive(Debug, Clone)]
+pub struct History<T> {
+    /// Oldest first, never empty.
+    past: VecDeque<T>,
+    /// Undone snapshots, the next one to redo last.
+    future: Vec<T>,
+    limit: usize,
+}
+
+impl<T> History<T> {
+    pub fn new(present: T, limit: usize) -> Self {
+        Self {
+            past: VecDeque::from([present]),
+            future: vec![],
+            limit: limit.max(1),
+        }
+    }
+
+    pub fn present(&self) -> &T {
+        self.past.back().expect("history is never empty")
+    }
+
+    /// Makes `snapshot` the present, forgetting anything undone.
+    pub fn push(&mut self, snapshot: T) {
+        self.future.clear();
+        self.past.push_back(snapshot);
+        while self.past.len() > self.limit {
+            self.past.pop_front();
+        }
+    }
+
+    /// Whether there is an older snapshot to go back to.
+    pub fn can_undo(&self) -> bool {
+        self.past.len() > 1
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+
+    /// Steps back to the previous snapshot and returns it. At the oldest one that is
+    /// returned again, so the caller can still restore it.
+    pub fn undo(&mut self) -> &T {
+        if self.can_undo() {
+            let present = self.past.pop_back().expect("history is never empty");
+            self.future.push(present);
+        }
+        self.present()
+    }
+
+    /// Steps forward to the snapshot last undone, if any.
+    pub fn redo(&mut self) -> Option<&T> {
+        let next = self.future.pop()?;
+        self.past.push_back(next);
+        Some(self.present())
+    }
+}
+
+/// Whether a piece was placed between `then` and `now` and the game is ready for the next one,
+/// lines cleared and the next piece in play or the game over.
+pub fn placed<R: Randomizer>(then: &Tetris<R>, now: &Tetris<R>) -> bool {
+    then.pieces_locked() != now.pieces_locked() && (now.game_over() || !now.spawning())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pushing_past_the_limit_drops_the_oldest() {
+        let mut history = History::new(0, 3);
+        for i in 1..=4 {
+            history.push(i);
+        }
+        assert_eq!(*history.present(), 4);
+        assert_eq!(*history.undo(), 3);
+        assert_eq!(*history.undo(), 2);
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn undo_stays_at_the_oldest() {
+        let mut history = History::new(0, 3);
+        history.push(1);
+        assert_eq!(*history.undo(), 0);
+        assert_eq!(*history.undo(), 0);
+        assert!(!history.can_undo());
+        // Only the one real undo can be redone.
+        assert_eq!(history.redo(), Some(&1));
+        assert_eq!(history.redo(), None);
+    }
+
+    #[test]
+    fn pushing_forgets_what_was_undone() {
+        let mut history = History::new(0, 3);
+        history.push(1);
+        history.push(2);
+        history.undo();
+        assert!(history.can_redo());
+        history.push(3);
+        assert!(!history.can_redo());
+        assert_eq!(history.redo(), None);
+        assert_eq!(*history.present(), 3);
+        assert_eq!(*history.undo(), 1);
+    }
+
+    #[test]
+    fn a_limit_of_zero_keeps_the_present() {
+        let mut history = History::new(0, 0);
+        history.push(1);
+        assert_eq!(*history.present(), 1);
+        assert!(!history.can_undo());
+    }
+}
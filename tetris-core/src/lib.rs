@@ -1,8 +1,23 @@
+pub mod action;
 pub mod board;
 pub mod brick;
 pub mod cell;
 pub mod color;
+pub mod error;
+pub mod event;
+pub mod generator;
+pub mod history;
+pub mod opener;
+pub mod palette;
+pub mod piece_set;
+pub mod placement;
 pub mod player;
 pub mod prelude;
+pub mod puzzle;
+pub mod ruleset;
+pub mod simulator;
+pub mod stats;
+#[cfg(feature = "test-util")]
+pub mod test_util;
 pub mod tetris;
 pub mod traits;
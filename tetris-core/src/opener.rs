@@ -0,0 +1,167 @@
+use crate::{
+    action::Action,
+    board::Board,
+    tetris::Tetris,
+    traits::{HasSize, Randomizer},
+};
+
+/// The name of a piece and the cells it covers.
+type Target = (String, Vec<(i32, i32)>);
+
+/// A known way to stack the first pieces of a game, given as the shape it builds with
+/// each bag of pieces.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Opener {
+    pub name: String,
+    /// The bottom rows of the well once each bag is placed, from the top down, each cell
+    /// the name of the tetromino that fills it or `.` for one left empty. Every drawing
+    /// keeps the cells of the one before it, and a piece appears at most once in the
+    /// cells a bag adds.
+    pub bags: Vec<Vec<String>>,
+}
+
+impl Opener {
+    /// The cells each piece of the shape covers on `board`, with the name of the piece,
+    /// those of the first bag first. Empty if a drawing is not as wide as the board or
+    /// leaves out cells of the one before it.
+    pub fn targets(&self, board: &Board) -> Vec<(String, Vec<(i32, i32)>)> {
+        self.by_bag(board).into_iter().flatten().collect()
+    }
+
+    /// The bag being placed on `board`, the first one with a cell still empty, or `None`
+    /// once the shape is built.
+    pub fn bag(&self, board: &Board) -> Option<usize> {
+        self.by_bag(board).iter().position(|targets| {
+            !targets
+                .iter()
+                .all(|(_, cells)| cells.iter().all(|&c| board.is_taken(c)))
+        })
+    }
+
+    /// The targets of every bag, only the cells it adds to the drawing before it.
+    fn by_bag(&self, board: &Board) -> Vec<Vec<Target>> {
+        let mut bags = vec![];
+        let mut placed: Vec<(i32, i32)> = vec![];
+        for shape in &self.bags {
+            let mut targets: Vec<Target> = vec![];
+            let mut filled = vec![];
+            let top = board.height() - shape.len() as i32;
+            for (dy, row) in shape.iter().enumerate() {
+                if row.chars().count() != board.width() as usize {
+                    return vec![];
+                }
+                for (x, c) in row.chars().enumerate().filter(|&(_, c)| c != '.') {
+                    let cell = (x as i32, top + dy as i32);
+                    filled.push(cell);
+                    if placed.contains(&cell) {
+                        continue;
+                    }
+                    match targets.iter_mut().find(|(name, _)| *name == c.to_string()) {
+                        Some((_, cells)) => cells.push(cell),
+                        None => targets.push((c.to_string(), vec![cell])),
+                    }
+                }
+            }
+            if placed.iter().any(|cell| !filled.contains(cell)) {
+                return vec![];
+            }
+            for (_, cells) in &mut targets {
+                cells.sort_unstable();
+            }
+            placed = filled;
+            bags.push(targets);
+        }
+        bags
+    }
+
+    /// Filled cells of `board` that are not part of the shape.
+    pub fn deviations(&self, board: &Board) -> Vec<(i32, i32)> {
+        let targets = self.targets(board);
+        let mut deviations = vec![];
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                let expected = targets.iter().any(|(_, cells)| cells.contains(&(x, y)));
+                if board.is_taken((x, y)) && !expected {
+                    deviations.push((x, y));
+                }
+            }
+        }
+        deviations
+    }
+
+    /// Whether every cell of the shape is filled.
+    pub fn is_built(&self, board: &Board) -> bool {
+        let targets = self.targets(board);
+        !targets.is_empty()
+            && targets
+                .iter()
+                .all(|(_, cells)| cells.iter().all(|&c| board.is_taken(c)))
+    }
+
+    /// Whether the pieces known so far, the one in play, the held one and the preview, can
+    /// still build the shape from where `tetris` is, each piece taken by any inputs that
+    /// reach it to its spot in the earliest bag that still has one open, holding where
+    /// allowed. Pieces past the preview are not known, so running out of them counts as
+    /// feasible.
+    pub fn feasible<R: Randomizer>(&self, tetris: &Tetris<R>) -> bool {
+        if self.targets(tetris.board()).is_empty() || !self.deviations(tetris.board()).is_empty() {
+            return false;
+        }
+        let known = 1 + tetris.queue().len();
+        self.search(tetris, known)
+    }
+
+    fn search<R: Randomizer>(&self, tetris: &Tetris<R>, known: usize) -> bool {
+        let board = tetris.board();
+        let remaining: Vec<Target> = self
+            .targets(board)
+            .into_iter()
+            .filter(|(_, cells)| !cells.iter().all(|&c| board.is_taken(c)))
+            .collect();
+        if remaining.is_empty() || known == 0 {
+            return true;
+        }
+        if tetris.game_over() || tetris.spawning() {
+            return false;
+        }
+
+        let mut starts = vec![(tetris.clone(), known)];
+        if tetris.can_hold() {
+            let mut held = tetris.clone();
+            // Holding into an empty slot brings out the next piece of the sequence.
+            let known = if held.held().is_none() {
+                known - 1
+            } else {
+                known
+            };
+            if known > 0 && held.apply(Action::Hold) {
+                starts.push((held, known));
+            }
+        }
+
+        for (start, known) in starts {
+            let name = &start.pieces().piece(start.player().brick().kind()).name;
+            // The first spot left for the piece, so a bag is finished before the next.
+            let Some((_, cells)) = remaining.iter().find(|(n, _)| n == name) else {
+                continue;
+            };
+            let placement = start.placements().into_iter().find(|p| p.cells() == *cells);
+            if let Some(placement) = placement {
+                let mut next = start.clone();
+                for &input in &placement.inputs {
+                    next.apply(input);
+                }
+                next.tick();
+                while next.spawning() && !next.game_over() {
+                    next.tick();
+                }
+                if self.search(&next, known - 1) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
@@ -0,0 +1,76 @@
+use crate::color::Color;
+
+/// Colours for each piece in [`Brick::by_index`](crate::brick::Brick::by_index) order
+/// (I, O, T, J, L, S, Z), plus the ghost and garbage cells.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Palette {
+    pub pieces: [Color; 7],
+    pub ghost: Color,
+    pub garbage: Color,
+}
+
+impl Palette {
+    pub const GUIDELINE: Palette = Palette {
+        pieces: [
+            Color(0, 255, 255),
+            Color(255, 255, 0),
+            Color(255, 0, 255),
+            Color(0, 0, 255),
+            Color(255, 165, 0),
+            Color(0, 255, 0),
+            Color(255, 0, 0),
+        ],
+        ghost: Color(255, 255, 255),
+        garbage: Color(128, 128, 128),
+    };
+
+    /// The level 0 colours of the NES version, where pieces share two colours and white.
+    pub const NES: Palette = Palette {
+        pieces: [
+            Color(252, 252, 252),
+            Color(252, 252, 252),
+            Color(252, 252, 252),
+            Color(0, 88, 248),
+            Color(60, 188, 252),
+            Color(0, 88, 248),
+            Color(60, 188, 252),
+        ],
+        ghost: Color(188, 188, 188),
+        garbage: Color(124, 124, 124),
+    };
+
+    pub const MONOCHROME: Palette = Palette {
+        pieces: [
+            Color(240, 240, 240),
+            Color(210, 210, 210),
+            Color(180, 180, 180),
+            Color(150, 150, 150),
+            Color(200, 200, 200),
+            Color(165, 165, 165),
+            Color(225, 225, 225),
+        ],
+        ghost: Color(255, 255, 255),
+        garbage: Color(90, 90, 90),
+    };
+
+    /// Okabe-Ito colours, distinguishable with the common forms of colour blindness.
+    pub const COLORBLIND: Palette = Palette {
+        pieces: [
+            Color(86, 180, 233),
+            Color(240, 228, 66),
+            Color(204, 121, 167),
+            Color(0, 114, 178),
+            Color(230, 159, 0),
+            Color(0, 158, 115),
+            Color(213, 94, 0),
+        ],
+        ghost: Color(255, 255, 255),
+        garbage: Color(110, 110, 110),
+    };
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::GUIDELINE
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{brick::Brick, cell::Cell, palette::Palette};
+
+/// Offsets tried in order when rotating, `y` growing downwards like the board.
+///
+/// Tables are indexed by the rotation state the piece rotates from (0 spawn, 1 right, 2 half
+/// turn, 3 left) and the direction.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Kicks {
+    clockwise: [Vec<(i32, i32)>; 4],
+    counter_clockwise: [Vec<(i32, i32)>; 4],
+}
+
+impl Kicks {
+    /// Rotation only succeeds in place.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// The same offsets for every rotation.
+    pub fn uniform(offsets: &[(i32, i32)]) -> Self {
+        let all = || std::array::from_fn(|_| offsets.to_vec());
+        Self {
+            clockwise: all(),
+            counter_clockwise: all(),
+        }
+    }
+
+    pub fn new(clockwise: [Vec<(i32, i32)>; 4], counter_clockwise: [Vec<(i32, i32)>; 4]) -> Self {
+        Self {
+            clockwise,
+            counter_clockwise,
+        }
+    }
+
+    /// Offsets to try when rotating from `rotation`. Never empty, the unmoved position is
+    /// tried when the table has no entry.
+    pub fn tests(&self, rotation: u8, clockwise: bool) -> &[(i32, i32)] {
+        let table = if clockwise {
+            &self.clockwise
+        } else {
+            &self.counter_clockwise
+        };
+        match table[rotation as usize % 4].as_slice() {
+            [] => &[(0, 0)],
+            tests => tests,
+        }
+    }
+
+    fn scaled(&self, factor: i32) -> Self {
+        let scale = |table: &[Vec<(i32, i32)>; 4]| {
+            table
+                .clone()
+                .map(|t| t.iter().map(|&(x, y)| (x * factor, y * factor)).collect())
+        };
+        Self {
+            clockwise: scale(&self.clockwise),
+            counter_clockwise: scale(&self.counter_clockwise),
+        }
+    }
+}
+
+/// Converts a table written the way the guideline does, `y` pointing up.
+fn srs(rows: [[(i32, i32); 5]; 4]) -> [Vec<(i32, i32)>; 4] {
+    rows.map(|row| row.iter().map(|&(x, y)| (x, -y)).collect())
+}
+
+fn srs_jlstz() -> Kicks {
+    Kicks::new(
+        srs([
+            [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+            [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+            [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+            [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+        ]),
+        srs([
+            [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+            [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+            [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+            [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+        ]),
+    )
+}
+
+fn srs_i() -> Kicks {
+    Kicks::new(
+        srs([
+            [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+            [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+            [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+            [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+        ]),
+        srs([
+            [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+            [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+            [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+            [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+        ]),
+    )
+}
+
+/// Kicks for sets without a standard table: one step sideways, then one up.
+fn basic_kicks() -> Kicks {
+    Kicks::uniform(&[(0, 0), (-1, 0), (1, 0), (0, -1)])
+}
+
+#[derive(Debug, Clone)]
+pub struct Piece {
+    pub name: String,
+    /// The piece in its spawn orientation.
+    pub brick: Brick,
+    pub kicks: Kicks,
+}
+
+impl Piece {
+    pub fn new(name: &str, brick: Brick, kicks: Kicks) -> Self {
+        Self {
+            name: name.to_owned(),
+            brick,
+            kicks,
+        }
+    }
+}
+
+/// The pieces a game draws from, each with its own rotation kicks.
+#[derive(Debug, Clone)]
+pub struct PieceSet {
+    pieces: Vec<Piece>,
+}
+
+impl Default for PieceSet {
+    fn default() -> Self {
+        Self::tetrominoes()
+    }
+}
+
+impl PieceSet {
+    /// Builds a set from pieces, renumbering their bricks to their position in the set.
+    pub fn new(pieces: Vec<Piece>) -> Self {
+        assert!(!pieces.is_empty(), "a piece set needs at least one piece");
+        let pieces = pieces
+            .into_iter()
+            .enumerate()
+            .map(|(kind, piece)| Piece {
+                brick: piece.brick.with_kind(kind),
+                ..piece
+            })
+            .collect();
+        Self { pieces }
+    }
+
+    /// The seven tetrominoes with SRS kicks.
+    pub fn tetrominoes() -> Self {
+        Self::new(vec![
+            Piece::new("I", Brick::i(), srs_i()),
+            Piece::new("O", Brick::o(), Kicks::none()),
+            Piece::new("T", Brick::t(), srs_jlstz()),
+            Piece::new("J", Brick::j(), srs_jlstz()),
+            Piece::new("L", Brick::l(), srs_jlstz()),
+            Piece::new("S", Brick::s(), srs_jlstz()),
+            Piece::new("Z", Brick::z(), srs_jlstz()),
+        ])
+    }
+
+    /// The eighteen one-sided pentominoes, mirrored pieces marked with a `'`.
+    #[rustfmt::skip]
+    pub fn pentominoes() -> Self {
+        Self::from_shapes(&[
+            ("I",  vec![".....", ".....", "#####", ".....", "....."]),
+            ("L",  vec![".....", "#....", "####.", ".....", "....."]),
+            ("L'", vec![".....", "...#.", "####.", ".....", "....."]),
+            ("N",  vec![".....", "##...", ".###.", ".....", "....."]),
+            ("N'", vec![".....", "...##", "###..", ".....", "....."]),
+            ("P",  vec![".....", ".##..", ".##..", ".#...", "....."]),
+            ("P'", vec![".....", ".##..", ".##..", "..#..", "....."]),
+            ("T",  vec![".....", ".###.", "..#..", "..#..", "....."]),
+            ("U",  vec![".....", ".#.#.", ".###.", ".....", "....."]),
+            ("V",  vec![".....", ".#...", ".#...", ".###.", "....."]),
+            ("W",  vec![".....", ".#...", ".##..", "..##.", "....."]),
+            ("X",  vec![".....", "..#..", ".###.", "..#..", "....."]),
+            ("Y",  vec![".....", "..#..", "####.", ".....", "....."]),
+            ("Y'", vec![".....", ".#...", ".####", ".....", "....."]),
+            ("Z",  vec![".....", ".##..", "..#..", "..##.", "....."]),
+            ("Z'", vec![".....", "..##.", "..#..", ".##..", "....."]),
+            ("F",  vec![".....", "..##.", ".##..", "..#..", "....."]),
+            ("F'", vec![".....", ".##..", "..##.", "..#..", "....."]),
+        ])
+    }
+
+    #[rustfmt::skip]
+    pub fn trominoes() -> Self {
+        Self::from_shapes(&[
+            ("I", vec!["...", "###", "..."]),
+            ("L", vec!["#.", "##"]),
+        ])
+    }
+
+    /// Pieces given as rows of text (see `Brick::from_rows`), coloured by cycling through the
+    /// guideline palette and using basic kicks.
+    pub fn from_shapes(shapes: &[(&str, Vec<&str>)]) -> Self {
+        let pieces = shapes.iter().enumerate().map(|(i, (name, rows))| {
+            let cell = Cell::Normal {
+                kind: i,
+                color: Palette::GUIDELINE.pieces[i % 7],
+            };
+            Piece::new(name, Brick::from_rows(i, rows, cell), basic_kicks())
+        });
+        Self::new(pieces.collect())
+    }
+
+    /// Every piece and kick scaled up by `factor`, e.g. 2 for big mode.
+    pub fn scaled(&self, factor: i32) -> Self {
+        let pieces = self.pieces.iter().map(|piece| Piece {
+            name: piece.name.clone(),
+            brick: piece.brick.scaled(factor),
+            kicks: piece.kicks.scaled(factor),
+        });
+        Self::new(pieces.collect())
+    }
+
+    pub fn len(&self) -> usize {
+        self.pieces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pieces.is_empty()
+    }
+
+    pub fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+
+    pub fn piece(&self, kind: usize) -> &Piece {
+        &self.pieces[kind % self.pieces.len()]
+    }
+
+    /// Spawn orientation brick for a random number, wrapping around the set.
+    pub fn brick(&self, i: i32) -> Brick {
+        self.pieces[i.rem_euclid(self.pieces.len() as i32) as usize].brick
+    }
+
+    pub fn kicks(&self, kind: usize, rotation: u8, clockwise: bool) -> &[(i32, i32)] {
+        self.piece(kind).kicks.tests(rotation, clockwise)
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{action::Action, board::Board, piece_set::PieceSet, player::Player, ruleset::Ruleset};
+
+/// Inputs tried from every state, hard drop excluded. Shifts count as one input, the way
+/// holding a direction with DAS does.
+const MOVES: [Action; 7] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::ShiftLeft,
+    Action::ShiftRight,
+    Action::RotateRight,
+    Action::RotateLeft,
+    Action::SoftDrop,
+];
+
+/// Where a piece comes to rest and the inputs that take it there.
+#[derive(Debug, Clone)]
+pub struct Placement {
+    /// The piece where it locks.
+    pub player: Player,
+    /// Shortest input sequence from the start, ending with a hard drop.
+    pub inputs: Vec<Action>,
+}
+
+impl Placement {
+    /// The locked cells, sorted, so placements of symmetric pieces compare equal.
+    pub fn cells(&self) -> Vec<(i32, i32)> {
+        sorted_cells(&self.player)
+    }
+}
+
+fn sorted_cells(player: &Player) -> Vec<(i32, i32)> {
+    let mut cells = player.cells();
+    cells.sort_unstable();
+    cells
+}
+
+/// What `action` does to `player` on `board`, or `None` if it is blocked. Rotations try the
+/// kicks of the rotation system in order. Hard drops and holds are not handled here.
+pub fn step(
+    board: &Board,
+    player: &Player,
+    action: Action,
+    ruleset: &Ruleset,
+    pieces: &PieceSet,
+) -> Option<Player> {
+    let fits = |p: &Player| p.brick_fits(board);
+    match action {
+        Action::MoveLeft => Some(player.move_left()).filter(fits),
+        Action::MoveRight => Some(player.move_right()).filter(fits),
+        Action::SoftDrop => Some(player.move_down()).filter(fits),
+        Action::ShiftLeft | Action::ShiftRight => {
+            let dx = if action == Action::ShiftLeft { -1 } else { 1 };
+            let mut moved = Some(player.moved_by((dx, 0))).filter(fits)?;
+            while fits(&moved.moved_by((dx, 0))) {
+                moved = moved.moved_by((dx, 0));
+            }
+            Some(moved)
+        }
+        Action::RotateLeft | Action::RotateRight => {
+            let clockwise = action == Action::RotateRight;
+            let rotated = if clockwise {
+                player.rotate_right()
+            } else {
+                player.rotate_left()
+            };
+            let kind = player.brick().kind();
+            ruleset
+                .rotation
+                .kicks(pieces, kind, player.rotation(), clockwise)
+                .iter()
+                .map(|&kick| rotated.moved_by(kick))
+                .find(fits)
+        }
+        Action::HardDrop | Action::Hold => None,
+    }
+}
+
+/// `player` dropped as far as it goes.
+pub fn dropped(board: &Board, player: &Player) -> Player {
+    let mut dropped = *player;
+    while dropped.move_down().brick_fits(board) {
+        dropped = dropped.move_down();
+    }
+    dropped
+}
+
+/// Every distinct place `start` can lock in, tucks and spins included, found breadth first
+/// so each comes with its shortest input sequence.
+pub fn placements(
+    board: &Board,
+    start: Player,
+    ruleset: &Ruleset,
+    pieces: &PieceSet,
+) -> Vec<Placement> {
+    if !start.brick_fits(board) {
+        return vec![];
+    }
+
+    let key = |p: &Player| (p.position(), p.rotation());
+    let mut parents: HashMap<((i32, i32), u8), (Player, Action)> = HashMap::new();
+    let mut seen = HashSet::from([key(&start)]);
+    let mut queue = VecDeque::from([start]);
+    let mut found = HashSet::new();
+    let mut placements = vec![];
+
+    while let Some(player) = queue.pop_front() {
+        // A soft drop lands where the state it came from does, nothing new to record.
+        let soft_dropped = parents
+            .get(&key(&player))
+            .is_some_and(|&(_, action)| action == Action::SoftDrop);
+        if !soft_dropped {
+            let rest = dropped(board, &player);
+            if found.insert(sorted_cells(&rest)) {
+                let mut inputs = vec![Action::HardDrop];
+                let mut at = player;
+                while let Some(&(parent, action)) = parents.get(&key(&at)) {
+                    inputs.push(action);
+                    at = parent;
+                }
+                inputs.reverse();
+                placements.push(Placement {
+                    player: rest,
+                    inputs,
+                });
+            }
+        }
+
+        for action in MOVES {
+            if let Some(next) = step(board, &player, action, ruleset, pieces) {
+                if seen.insert(key(&next)) {
+                    parents.insert(key(&next), (player, action));
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+    placements
+}
@@ -9,6 +9,8 @@ use crate::{
 pub struct Player {
     position: (i32, i32),
     brick: Brick,
+    /// Quarter turns clockwise from the spawn orientation.
+    rotation: u8,
 }
 
 impl Player {
@@ -20,10 +22,19 @@ impl Player {
     }
 
     pub fn with_brick_centered(brick: Brick, width: i32) -> Self {
-        let x = width / 2 - brick.width() / 2;
+        let x = (width - brick.width()) / 2;
         Self {
             position: (x, 0),
             brick,
+            rotation: 0,
+        }
+    }
+
+    pub fn with_brick_at(brick: Brick, position: (i32, i32)) -> Self {
+        Self {
+            position,
+            brick,
+            rotation: 0,
         }
     }
 
@@ -36,17 +47,30 @@ impl Player {
         self.position
     }
 
+    pub fn rotation(&self) -> u8 {
+        self.rotation
+    }
+
     pub fn rotate_left(&self) -> Self {
         Self {
-            position: self.position,
             brick: self.brick.rotate_left(),
+            rotation: (self.rotation + 3) % 4,
+            ..*self
         }
     }
 
     pub fn rotate_right(&self) -> Self {
         Self {
-            position: self.position,
             brick: self.brick.rotate_right(),
+            rotation: (self.rotation + 1) % 4,
+            ..*self
+        }
+    }
+
+    pub fn moved_by(&self, (dx, dy): (i32, i32)) -> Self {
+        Self {
+            position: (self.position.0 + dx, self.position.1 + dy),
+            ..*self
         }
     }
 
@@ -75,12 +99,29 @@ impl Player {
         board.brick_fits(self.position, self.brick)
     }
 
+    /// Board coordinates of every cell the brick occupies.
+    pub fn cells(&self) -> Vec<(i32, i32)> {
+        let (ox, oy) = self.position;
+        let mut cells = vec![];
+        self.brick.iter_dim(|x, y, c| {
+            if c.is_some() {
+                cells.push((ox + x, oy + y));
+            }
+        });
+        cells
+    }
+
     pub fn brick(&self) -> Brick {
         self.brick
     }
 
+    /// Swaps in `brick`, which is taken to be in its spawn orientation.
     pub fn set_brick(&self, brick: Brick) -> Self {
-        Self { brick, ..*self }
+        Self {
+            brick,
+            rotation: 0,
+            ..*self
+        }
     }
 }
 
@@ -1,7 +1,16 @@
+pub use crate::action::{Action, Replay};
 pub use crate::board::Board;
 pub use crate::brick::Brick;
 pub use crate::cell::Cell;
 pub use crate::color::Color;
+pub use crate::error::{PuzzleError, TetrisError};
+pub use crate::event::Event;
+pub use crate::palette::Palette;
+pub use crate::piece_set::{Piece, PieceSet};
+pub use crate::placement::Placement;
 pub use crate::player::Player;
+pub use crate::puzzle::Puzzle;
+pub use crate::ruleset::Ruleset;
+pub use crate::simulator::Simulator;
 pub use crate::tetris::Tetris;
 pub use crate::traits::{HasSize, IterateDimensions};
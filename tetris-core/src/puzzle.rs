@@ -0,0 +1,292 @@
+use std::{collections::HashSet, fmt::Display};
+
+use crate::{
+    action::Action,
+    board::{self, Board},
+    error::PuzzleError,
+    event::Event,
+    history::{self, History},
+    piece_set::PieceSet,
+    ruleset::{HoldRule, Ruleset},
+    tetris::Tetris,
+    traits::Randomizer,
+};
+
+/// What a puzzle asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Goal {
+    /// Clear at least this many lines in total.
+    Lines(u32),
+    /// Leave the board completely empty after a clear.
+    PerfectClear,
+    /// Clear two lines with a single T-spin.
+    TSpinDouble,
+}
+
+impl Default for Goal {
+    fn default() -> Self {
+        Self::Lines(1)
+    }
+}
+
+impl Display for Goal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Goal::Lines(1) => write!(f, "Clear a line"),
+            Goal::Lines(n) => write!(f, "Clear {n} lines"),
+            Goal::PerfectClear => write!(f, "Perfect clear"),
+            Goal::TSpinDouble => write!(f, "T-spin double"),
+        }
+    }
+}
+
+/// A board to start from, the exact pieces to play on it and what to achieve with them.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Puzzle {
+    pub name: String,
+    /// The bottom rows of the well from the top down, `#` for filled cells and `.` for empty
+    /// ones. Empty rows are added above up to the usual height.
+    pub board: Vec<String>,
+    /// Names of the tetrominoes to play, in order.
+    pub pieces: Vec<String>,
+    pub goal: Goal,
+    /// Whether the hold slot may be used.
+    pub hold: bool,
+}
+
+impl Default for Puzzle {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            board: vec![".".repeat(board::WIDTH as usize)],
+            pieces: vec![],
+            goal: Goal::default(),
+            hold: true,
+        }
+    }
+}
+
+impl Puzzle {
+    /// Guideline rules without gravity, showing every piece of the sequence.
+    pub fn ruleset(&self) -> Ruleset {
+        Ruleset {
+            name: self.name.clone(),
+            gravity: vec![0.0],
+            preview: self.pieces.len().max(1),
+            hold: if self.hold {
+                HoldRule::OncePerPiece
+            } else {
+                HoldRule::Disabled
+            },
+            ..Ruleset::guideline()
+        }
+    }
+
+    /// The game at the start of the puzzle and the run keeping track of the goal.
+    pub fn start<R: Randomizer>(
+        &self,
+        randomizer: R,
+    ) -> Result<(Tetris<R>, PuzzleRun<R>), PuzzleError> {
+        let width = self.board.first().map_or(0, |row| row.chars().count());
+        if width == 0 {
+            return Err(PuzzleError::NoColumns);
+        }
+        if let Some(row) = self.board.iter().find(|row| row.chars().count() != width) {
+            return Err(PuzzleError::UnevenRow {
+                row: row.clone(),
+                width,
+            });
+        }
+        if let Some(c) = self.board.concat().chars().find(|&c| c != '#' && c != '.') {
+            return Err(PuzzleError::UnexpectedCell(c));
+        }
+
+        let pieces = PieceSet::tetrominoes();
+        let queue = self
+            .pieces
+            .iter()
+            .map(|name| {
+                let piece = pieces.pieces().iter().find(|p| p.name == *name);
+                piece
+                    .map(|p| p.brick)
+                    .ok_or_else(|| PuzzleError::UnknownPiece(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if queue.is_empty() {
+            return Err(PuzzleError::NoPieces);
+        }
+
+        let padding = (board::HEIGHT as usize).saturating_sub(self.board.len());
+        let empty = ".".repeat(width);
+        let rows: Vec<&str> = std::iter::repeat_n(empty.as_str(), padding)
+            .chain(self.board.iter().map(String::as_str))
+            .collect();
+        let ruleset = self.ruleset();
+        ruleset.check_board(width as i32, rows.len() as i32, &pieces)?;
+        let board = Board::from_rows(&rows, ruleset.buffer_rows as i32);
+
+        let tetris = Tetris::with_setup(board, queue, ruleset, pieces, randomizer)?;
+        let run = PuzzleRun::new(self.goal, &tetris);
+        Ok((tetris, run))
+    }
+
+    /// Inputs that solve the puzzle, found by trying every placement of every piece, with
+    /// and without hold. `None` if there is no solution or the puzzle does not load.
+    pub fn solve<R: Randomizer>(&self) -> Option<Vec<Action>> {
+        let (tetris, run) = self.start(R::default()).ok()?;
+        let mut seen = HashSet::new();
+        let mut inputs = vec![];
+        search(tetris, run, &mut seen, &mut inputs).then_some(inputs)
+    }
+}
+
+/// Depth first search for a solution from `tetris`, leaving the inputs of it in `inputs`.
+fn search<R: Randomizer>(
+    tetris: Tetris<R>,
+    run: PuzzleRun<R>,
+    seen: &mut HashSet<String>,
+    inputs: &mut Vec<Action>,
+) -> bool {
+    match run.status(&tetris) {
+        Status::Solved => return true,
+        Status::Failed => return false,
+        Status::Playing => {}
+    }
+    // Positions are told apart by the board and what is left to play.
+    let key = format!(
+        "{}{:?}{:?}{:?}{:?}",
+        tetris.board(),
+        tetris.player().brick().kind(),
+        tetris.held().map(|b| b.kind()),
+        tetris.queue().iter().map(|b| b.kind()).collect::<Vec<_>>(),
+        run.progress,
+    );
+    if !seen.insert(key) {
+        return false;
+    }
+
+    let mut starts = vec![(tetris.clone(), vec![])];
+    if tetris.can_hold() {
+        let mut held = tetris.clone();
+        if held.swap_held() {
+            starts.push((held, vec![Action::Hold]));
+        }
+    }
+
+    for (start, hold) in starts {
+        for placement in start.placements() {
+            let mut next = start.clone();
+            let mut run = run.clone();
+            for &input in &placement.inputs {
+                next.apply(input);
+            }
+            next.tick();
+            let events = next.drain_events();
+            run.observe(&next, &events);
+
+            let len = inputs.len();
+            inputs.extend(hold.iter().chain(&placement.inputs));
+            if search(next, run, seen, inputs) {
+                return true;
+            }
+            inputs.truncate(len);
+        }
+    }
+    false
+}
+
+/// What has been achieved towards the goal so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Progress {
+    pub lines: u32,
+    pub t_spin_doubles: u32,
+    pub perfect_clears: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Playing,
+    Solved,
+    /// The pieces ran out or the stack topped out before the goal was met.
+    Failed,
+}
+
+/// Follows a game of a puzzle: checks the goal and remembers the game before every piece
+/// so placements can be taken back and replayed.
+#[derive(Debug, Clone)]
+pub struct PuzzleRun<R> {
+    pub goal: Goal,
+    pub progress: Progress,
+    /// The game as it was when each piece came into play.
+    history: History<(Tetris<R>, Progress)>,
+}
+
+impl<R: Randomizer> PuzzleRun<R> {
+    pub fn new(goal: Goal, tetris: &Tetris<R>) -> Self {
+        Self {
+            goal,
+            progress: Progress::default(),
+            // Puzzles are short, every piece of one can be taken back.
+            history: History::new((tetris.clone(), Progress::default()), usize::MAX),
+        }
+    }
+
+    /// Call every step with the events `tetris` gave out in it.
+    pub fn observe(&mut self, tetris: &Tetris<R>, events: &[Event]) {
+        for event in events {
+            match event {
+                Event::LinesCleared { rows, .. } => {
+                    self.progress.lines += rows.len() as u32;
+                    if tetris.board().is_empty() {
+                        self.progress.perfect_clears += 1;
+                    }
+                }
+                Event::TSpin { lines: 2 } => self.progress.t_spin_doubles += 1,
+                _ => {}
+            }
+        }
+
+        if history::placed(&self.history.present().0, tetris) {
+            self.history.push((tetris.clone(), self.progress));
+        }
+    }
+
+    pub fn status(&self, tetris: &Tetris<R>) -> Status {
+        let solved = match self.goal {
+            Goal::Lines(n) => self.progress.lines >= n,
+            Goal::PerfectClear => self.progress.perfect_clears > 0,
+            Goal::TSpinDouble => self.progress.t_spin_doubles > 0,
+        };
+        if solved {
+            Status::Solved
+        } else if tetris.game_over() {
+            Status::Failed
+        } else {
+            Status::Playing
+        }
+    }
+
+    /// Takes back the last piece placed, or puts the current one back where it spawned
+    /// when none has been. Returns whether any piece was placed before.
+    pub fn undo(&mut self, tetris: &mut Tetris<R>) -> bool {
+        let undone = self.history.can_undo();
+        let (last, progress) = self.history.undo();
+        *tetris = last.clone();
+        self.progress = *progress;
+        undone
+    }
+
+    /// Places again the piece last taken back, returning whether there was one.
+    pub fn redo(&mut self, tetris: &mut Tetris<R>) -> bool {
+        let Some((next, progress)) = self.history.redo() else {
+            return false;
+        };
+        *tetris = next.clone();
+        self.progress = *progress;
+        true
+    }
+}
@@ -0,0 +1,368 @@
+use crate::{
+    board::Board, brick::Brick, error::TetrisError, piece_set::PieceSet, player::Player,
+    traits::HasSize,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RotationSystem {
+    /// The kicks of the piece set, SRS for the tetrominoes.
+    #[default]
+    Srs,
+    /// Rotation only succeeds in place.
+    Classic,
+    /// One step right, then one step left, like the arcade games.
+    Tgm,
+}
+
+impl RotationSystem {
+    pub fn kicks<'a>(
+        &self,
+        pieces: &'a PieceSet,
+        kind: usize,
+        rotation: u8,
+        clockwise: bool,
+    ) -> &'a [(i32, i32)] {
+        match self {
+            RotationSystem::Srs => pieces.kicks(kind, rotation, clockwise),
+            RotationSystem::Classic => &[(0, 0)],
+            RotationSystem::Tgm => &[(0, 0), (1, 0), (-1, 0)],
+        }
+    }
+}
+
+/// How the next piece is picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Generator {
+    /// Every piece is equally likely every time.
+    Random,
+    /// Every piece of the set once, shuffled, then the next bag.
+    #[default]
+    Bag,
+    /// Rerolls up to `rolls` times while the piece is one of the last `size` pieces.
+    History { size: usize, rolls: u32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum HoldRule {
+    Disabled,
+    /// Once until the next piece locks, the held piece comes back at the spawn position.
+    #[default]
+    OncePerPiece,
+    /// Any number of times, swapping pieces in place.
+    Unlimited,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Scoring {
+    /// Points for clearing 1, 2, 3, 4... lines at once, the last entry counts for larger clears.
+    pub lines: Vec<u32>,
+    /// Whether line clear and combo points are multiplied by the level.
+    pub level_multiplier: bool,
+    /// Points for every step of a combo.
+    pub combo: u32,
+    /// Points per row soft dropped.
+    pub soft_drop: u32,
+    /// Points per row hard dropped.
+    pub hard_drop: u32,
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self {
+            lines: vec![100, 300, 500, 800],
+            level_multiplier: true,
+            combo: 50,
+            soft_drop: 1,
+            hard_drop: 2,
+        }
+    }
+}
+
+impl Scoring {
+    pub fn line_clear(&self, lines: u32) -> u32 {
+        let index = (lines as usize).saturating_sub(1);
+        self.lines
+            .get(index)
+            .or(self.lines.last())
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Garbage {
+    /// Pieces locked between rising garbage, 0 turns it off.
+    pub interval: u32,
+    /// Rows pushed up each time, each with a single random hole.
+    pub rows: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Spawn {
+    /// Column of the left edge of the piece box, centred when unset.
+    pub column: Option<i32>,
+    /// Row of the top edge of the piece box, in the buffer above the field when unset.
+    pub row: Option<i32>,
+}
+
+impl Spawn {
+    /// Column of the left edge of `brick` on a board `width` wide. Unless set, the box is
+    /// centred and rounded to the left, which on ten columns puts three wide pieces in the
+    /// fourth to sixth column and the I and O in the middle, like the guideline.
+    pub fn column_for(&self, width: i32, brick: &Brick) -> i32 {
+        self.column.unwrap_or((width - brick.width()) / 2)
+    }
+
+    /// Row of the top edge of a piece box on a board with `buffer` rows. Unless set, it is
+    /// as far into the buffer as two rows, where the guideline spawns pieces.
+    pub fn row_for(&self, buffer: i32) -> i32 {
+        self.row.unwrap_or(-buffer.min(2))
+    }
+
+    /// Where `brick` enters `board`. Like the guideline, a piece spawned in the buffer drops
+    /// a row at once when nothing is in its way, so part of it shows straight away.
+    pub fn player_for(&self, board: &Board, brick: Brick) -> Player {
+        let x = self.column_for(board.width(), &brick);
+        let player = Player::with_brick_at(brick, (x, self.row_for(board.buffer())));
+        let dropped = player.moved_by((0, 1));
+        if self.row.is_none()
+            && player.position().1 < 0
+            && player.brick_fits(board)
+            && dropped.brick_fits(board)
+        {
+            dropped
+        } else {
+            player
+        }
+    }
+}
+
+/// Everything that makes one game of falling blocks differ from another.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Ruleset {
+    pub name: String,
+    pub rotation: RotationSystem,
+    pub generator: Generator,
+    /// Upcoming pieces shown, none with 0.
+    pub preview: usize,
+    pub hold: HoldRule,
+    /// Frames a piece may rest on the stack before locking. With 0 it locks on the next
+    /// gravity step instead.
+    pub lock_delay: u32,
+    /// Moves and rotations that restart the lock delay, unlimited when unset. Reaching a
+    /// lower row gives all of them back.
+    pub lock_resets: Option<u32>,
+    /// Rows fallen per frame for each level starting at level 1, the last entry is used
+    /// for every level after it.
+    pub gravity: Vec<f32>,
+    pub lines_per_level: u32,
+    pub scoring: Scoring,
+    pub garbage: Garbage,
+    pub spawn: Spawn,
+    /// Hidden rows above the visible playfield that pieces can move, rotate and lock in.
+    pub buffer_rows: u32,
+    /// Frames between a piece locking and the next one appearing.
+    pub are: u32,
+    /// Frames added to the spawn delay when the locked piece cleared lines.
+    pub line_clear_delay: u32,
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Self::guideline()
+    }
+}
+
+impl Ruleset {
+    pub const PRESETS: &'static [&'static str] = &["guideline", "nes", "tgm", "casual"];
+
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "guideline" => Some(Self::guideline()),
+            "nes" => Some(Self::nes()),
+            "tgm" => Some(Self::tgm()),
+            "casual" => Some(Self::casual()),
+            _ => None,
+        }
+    }
+
+    /// Modern rules: SRS, 7-bag, five previews, hold and a forgiving lock delay.
+    pub fn guideline() -> Self {
+        // Seconds per row from the guideline: (0.8 - (level - 1) * 0.007) ^ (level - 1).
+        let mut gravity: Vec<f32> = (0..15)
+            .map(|l| 1.0 / ((0.8 - l as f32 * 0.007).powi(l) * 60.0))
+            .collect();
+        gravity.push(20.0);
+
+        Self {
+            name: "Guideline".to_owned(),
+            rotation: RotationSystem::Srs,
+            generator: Generator::Bag,
+            preview: 5,
+            hold: HoldRule::OncePerPiece,
+            lock_delay: 30,
+            lock_resets: Some(15),
+            gravity,
+            lines_per_level: 10,
+            scoring: Scoring::default(),
+            garbage: Garbage::default(),
+            spawn: Spawn::default(),
+            buffer_rows: 20,
+            are: 0,
+            line_clear_delay: 0,
+        }
+    }
+
+    /// One preview, no hold, no kicks and pieces lock as soon as they touch down.
+    pub fn nes() -> Self {
+        const FRAMES_PER_ROW: [u32; 20] = [
+            48, 43, 38, 33, 28, 23, 18, 13, 8, 6, 5, 5, 5, 4, 4, 4, 3, 3, 3, 2,
+        ];
+        let mut gravity: Vec<f32> = FRAMES_PER_ROW.iter().map(|&f| 1.0 / f as f32).collect();
+        gravity.extend([0.5; 9]);
+        gravity.push(1.0);
+
+        Self {
+            name: "NES".to_owned(),
+            rotation: RotationSystem::Classic,
+            generator: Generator::History { size: 1, rolls: 2 },
+            preview: 1,
+            hold: HoldRule::Disabled,
+            lock_delay: 0,
+            lock_resets: Some(0),
+            gravity,
+            lines_per_level: 10,
+            scoring: Scoring {
+                lines: vec![40, 100, 300, 1200],
+                level_multiplier: true,
+                combo: 0,
+                soft_drop: 1,
+                hard_drop: 0,
+            },
+            garbage: Garbage::default(),
+            spawn: Spawn::default(),
+            buffer_rows: 0,
+            are: 10,
+            line_clear_delay: 18,
+        }
+    }
+
+    /// Arcade style: a piece history generator, simple kicks and gravity that reaches 20G.
+    pub fn tgm() -> Self {
+        const INTERNAL_GRAVITY: [u32; 25] = [
+            4, 6, 8, 10, 12, 16, 32, 48, 64, 80, 96, 112, 128, 144, 160, 192, 224, 256, 512, 768,
+            1024, 1280, 1024, 768, 5120,
+        ];
+
+        Self {
+            name: "TGM".to_owned(),
+            rotation: RotationSystem::Tgm,
+            generator: Generator::History { size: 4, rolls: 4 },
+            preview: 1,
+            hold: HoldRule::Disabled,
+            lock_delay: 30,
+            lock_resets: Some(0),
+            gravity: INTERNAL_GRAVITY.iter().map(|&g| g as f32 / 256.0).collect(),
+            lines_per_level: 10,
+            scoring: Scoring {
+                lines: vec![100, 400, 900, 1600],
+                level_multiplier: true,
+                combo: 0,
+                soft_drop: 1,
+                hard_drop: 0,
+            },
+            garbage: Garbage::default(),
+            spawn: Spawn::default(),
+            buffer_rows: 2,
+            are: 30,
+            line_clear_delay: 41,
+        }
+    }
+
+    /// Slow gravity, a long lock delay that can always be reset and unlimited hold.
+    pub fn casual() -> Self {
+        Self {
+            name: "Casual".to_owned(),
+            hold: HoldRule::Unlimited,
+            lock_delay: 60,
+            lock_resets: None,
+            gravity: vec![1.0 / 60.0, 1.0 / 50.0, 1.0 / 40.0, 1.0 / 30.0, 1.0 / 25.0],
+            scoring: Scoring {
+                level_multiplier: false,
+                ..Scoring::default()
+            },
+            ..Self::guideline()
+        }
+    }
+
+    /// Checks that every piece of `pieces` can spawn and turn on a board of `width` by
+    /// `height` visible rows under these rules, explaining what does not fit otherwise.
+    pub fn check_board(
+        &self,
+        width: i32,
+        height: i32,
+        pieces: &PieceSet,
+    ) -> Result<(), TetrisError> {
+        self.check_board_with_buffer(width, height, self.buffer_rows as i32, pieces)
+    }
+
+    /// [`Ruleset::check_board`] for a board with `buffer` rows rather than the ruleset's own.
+    pub(crate) fn check_board_with_buffer(
+        &self,
+        width: i32,
+        height: i32,
+        buffer: i32,
+        pieces: &PieceSet,
+    ) -> Result<(), TetrisError> {
+        Board::check_size(width, height, buffer)?;
+        for piece in pieces.pieces() {
+            // Every orientation of a piece stays within its square box.
+            let size = piece.brick.width();
+            if size > width || size > height {
+                return Err(TetrisError::PieceTooLarge {
+                    piece: piece.name.clone(),
+                    size,
+                    width,
+                    height,
+                });
+            }
+            let column = self.spawn.column_for(width, &piece.brick);
+            let row = self.spawn.row_for(buffer);
+            if column < 0 || column + size > width || row < -buffer || row + size > height {
+                return Err(TetrisError::SpawnOutside {
+                    piece: piece.name.clone(),
+                    position: (column, row),
+                    size,
+                    width,
+                    height,
+                    buffer,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Rows fallen per frame at `level`.
+    pub fn gravity(&self, level: u32) -> f32 {
+        let index = (level as usize).saturating_sub(1);
+        self.gravity
+            .get(index)
+            .or(self.gravity.last())
+            .copied()
+            .unwrap_or(0.0)
+    }
+}
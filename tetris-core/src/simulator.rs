@@ -0,0 +1,230 @@
+use std::{
+    num::NonZeroUsize,
+    panic,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::{
+    action::Action,
+    board::{HEIGHT, WIDTH},
+    error::TetrisError,
+    piece_set::PieceSet,
+    ruleset::Ruleset,
+    stats::{per, FRAMES_PER_SECOND},
+    tetris::Tetris,
+    traits::Randomizer,
+};
+
+/// Plays many games without a frontend, one for each seed, spread over threads. Used to
+/// tune rulesets and compare bots.
+#[derive(Debug, Clone)]
+pub struct Simulator {
+    ruleset: Ruleset,
+    pieces: PieceSet,
+    size: (i32, i32),
+    /// Frames after which a game that has not topped out is stopped.
+    max_frames: u32,
+    threads: usize,
+}
+
+impl Simulator {
+    /// Games on a standard board, stopped after five minutes, on every available core.
+    pub fn new(ruleset: Ruleset, pieces: PieceSet) -> Self {
+        Self {
+            ruleset,
+            pieces,
+            size: (WIDTH as i32, HEIGHT as i32),
+            max_frames: 5 * 60 * FRAMES_PER_SECOND as u32,
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        }
+    }
+
+    pub fn with_size(self, width: i32, height: i32) -> Self {
+        Self {
+            size: (width, height),
+            ..self
+        }
+    }
+
+    pub fn with_max_frames(self, max_frames: u32) -> Self {
+        Self { max_frames, ..self }
+    }
+
+    pub fn with_threads(self, threads: usize) -> Self {
+        Self {
+            threads: threads.max(1),
+            ..self
+        }
+    }
+
+    /// Plays a game for every seed, in the order of `seeds`. `new_player` is called once per
+    /// game and the player it returns once per frame, before the frame is stepped, with the
+    /// input to apply if any.
+    pub fn run<R, F, P>(&self, seeds: &[i32], new_player: F) -> Result<Vec<GameResult>, TetrisError>
+    where
+        R: Randomizer,
+        F: Fn() -> P + Sync,
+        P: FnMut(&Tetris<R>) -> Option<Action>,
+    {
+        let (width, height) = self.size;
+        self.ruleset.check_board(width, height, &self.pieces)?;
+
+        // Threads take the next seed until there are none left, so one long game does not
+        // hold up a share of the others.
+        let next = AtomicUsize::new(0);
+        let mut results: Vec<(usize, GameResult)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.threads.min(seeds.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = vec![];
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(&seed) = seeds.get(i) else {
+                                break;
+                            };
+                            done.push((i, self.play(seed, new_player())));
+                        }
+                        done
+                    })
+                })
+                .collect();
+            // A panic in `new_player` or its player goes on to the caller as it was.
+            workers
+                .into_iter()
+                .flat_map(|w| w.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+                .collect()
+        });
+        results.sort_by_key(|&(i, _)| i);
+        Ok(results.into_iter().map(|(_, result)| result).collect())
+    }
+
+    fn play<R, P>(&self, seed: i32, mut player: P) -> GameResult
+    where
+        R: Randomizer,
+        P: FnMut(&Tetris<R>) -> Option<Action>,
+    {
+        let (width, height) = self.size;
+        let mut tetris = Tetris::with_ruleset(
+            width,
+            height,
+            self.ruleset.clone(),
+            self.pieces.clone(),
+            R::with_seed(seed),
+        )
+        .expect("the board was checked");
+        while !tetris.game_over() && tetris.frame() < self.max_frames {
+            if let Some(action) = player(&tetris) {
+                tetris.apply(action);
+            }
+            tetris.tick();
+            tetris.drain_events();
+        }
+        GameResult {
+            seed,
+            score: tetris.score(),
+            lines: tetris.lines(),
+            level: tetris.level(),
+            pieces: tetris.pieces_locked(),
+            frames: tetris.frame(),
+            topped_out: tetris.game_over(),
+        }
+    }
+}
+
+/// How one simulated game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameResult {
+    pub seed: i32,
+    pub score: u32,
+    pub lines: u32,
+    pub level: u32,
+    pub pieces: u32,
+    pub frames: u32,
+    /// Whether the game ended by topping out rather than running out of frames.
+    pub topped_out: bool,
+}
+
+impl GameResult {
+    pub const CSV_HEADER: &'static str = "seed,score,lines,level,pieces,frames,topped_out";
+
+    pub fn csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{}",
+            self.seed,
+            self.score,
+            self.lines,
+            self.level,
+            self.pieces,
+            self.frames,
+            self.topped_out
+        )
+    }
+}
+
+/// Figures over a batch of simulated games.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Summary {
+    pub games: usize,
+    pub mean_lines: f32,
+    pub mean_score: f32,
+    /// Lowest, first quartile, median, third quartile and highest score.
+    pub scores: [u32; 5],
+    pub mean_pieces: f32,
+    /// Share of the games that topped out, from 0 to 1.
+    pub top_out_rate: f32,
+}
+
+impl Summary {
+    pub const CSV_HEADER: &'static str = "name,games,mean_lines,mean_score,min_score,\
+        q1_score,median_score,q3_score,max_score,mean_pieces,top_out_rate";
+
+    pub fn new(results: &[GameResult]) -> Self {
+        if results.is_empty() {
+            return Self::default();
+        }
+        let games = results.len() as f32;
+        let mean = |value: fn(&GameResult) -> u32| {
+            per(results.iter().map(|r| value(r) as f32).sum(), games)
+        };
+        let mut scores: Vec<u32> = results.iter().map(|r| r.score).collect();
+        scores.sort_unstable();
+        // The score closest to the given share of the way through, lowest first.
+        let quantile = |q: f32| scores[((scores.len() - 1) as f32 * q).round() as usize];
+        Self {
+            games: results.len(),
+            mean_lines: mean(|r| r.lines),
+            mean_score: mean(|r| r.score),
+            scores: [0.0, 0.25, 0.5, 0.75, 1.0].map(quantile),
+            mean_pieces: mean(|r| r.pieces),
+            top_out_rate: per(
+                results.iter().filter(|r| r.topped_out).count() as f32,
+                games,
+            ),
+        }
+    }
+
+    /// The summary as a line under [`Summary::CSV_HEADER`], `name` telling the ruleset or
+    /// bot it was made with apart from the other lines.
+    pub fn csv_row(&self, name: &str) -> String {
+        let [min, q1, median, q3, max] = self.scores;
+        format!(
+            "{},{},{:.2},{:.2},{min},{q1},{median},{q3},{max},{:.2},{:.4}",
+            csv_field(name),
+            self.games,
+            self.mean_lines,
+            self.mean_score,
+            self.mean_pieces,
+            self.top_out_rate
+        )
+    }
+}
+
+/// Quotes `field` if it has commas, quotes or line breaks in it.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
@@ -0,0 +1,91 @@
+/// Simulation steps per second, the rate gravity and delays are given in.
+pub const FRAMES_PER_SECOND: f32 = 60.0;
+
+/// Counters kept over a whole game.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Stats {
+    pub pieces: u32,
+    /// Keys pressed, once for a key held down however often it repeats. Counted by the
+    /// frontend through [`Tetris::count_keys`](crate::tetris::Tetris::count_keys).
+    pub keys: u32,
+    pub holds: u32,
+    /// Line clears by size: singles, doubles, triples and tetrises or larger.
+    pub clears: [u32; 4],
+    /// T-spins by the lines they cleared, from none to three.
+    pub t_spins: [u32; 4],
+    pub max_combo: u32,
+    /// Tetrises and T-spin clears in a row without another clear in between.
+    pub back_to_back: u32,
+    pub max_back_to_back: u32,
+    pub frames: u32,
+}
+
+impl Stats {
+    pub fn seconds(&self) -> f32 {
+        self.frames as f32 / FRAMES_PER_SECOND
+    }
+
+    /// Pieces per second.
+    pub fn pps(&self) -> f32 {
+        per(self.pieces as f32, self.seconds())
+    }
+
+    /// Keys per piece.
+    pub fn kpp(&self) -> f32 {
+        per(self.keys as f32, self.pieces as f32)
+    }
+
+    pub(crate) fn cleared(&mut self, lines: u32, t_spin: bool, combo: u32) {
+        self.clears[(lines as usize).clamp(1, 4) - 1] += 1;
+        self.max_combo = self.max_combo.max(combo);
+        if lines >= 4 || t_spin {
+            self.back_to_back += 1;
+            self.max_back_to_back = self.max_back_to_back.max(self.back_to_back);
+        } else {
+            self.back_to_back = 0;
+        }
+    }
+}
+
+/// `amount / over`, 0 when there is nothing to divide by.
+pub fn per(amount: f32, over: f32) -> f32 {
+    if over > 0.0 {
+        amount / over
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tetrises_and_t_spins_keep_back_to_back_going() {
+        let mut stats = Stats::default();
+        stats.cleared(1, false, 0);
+        assert_eq!(stats.back_to_back, 0);
+        stats.cleared(4, false, 1);
+        stats.cleared(1, true, 2);
+        stats.cleared(5, false, 3);
+        assert_eq!(stats.back_to_back, 3);
+        stats.cleared(2, false, 0);
+        assert_eq!(stats.back_to_back, 0);
+        stats.cleared(4, false, 1);
+        assert_eq!(stats.back_to_back, 1);
+        assert_eq!(stats.max_back_to_back, 3);
+        // Clears of five lines and more count with the tetrises.
+        assert_eq!(stats.clears, [2, 1, 0, 3]);
+    }
+
+    #[test]
+    fn the_longest_combo_is_kept() {
+        let mut stats = Stats::default();
+        for combo in [0, 1, 2, 0, 1] {
+            stats.cleared(1, false, combo);
+        }
+        assert_eq!(stats.max_combo, 2);
+    }
+}
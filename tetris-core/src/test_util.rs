@@ -0,0 +1,22 @@
+//! Fixtures for the tests and benches of this crate and the crates built on it.
+
+use crate::traits::Randomizer;
+
+/// Counts up from the seed, so every game can be played again.
+#[derive(Debug, Clone, Default)]
+pub struct Counter(i32);
+
+impl Randomizer for Counter {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_seed(seed: i32) -> Self {
+        Self(seed)
+    }
+
+    fn next(&mut self) -> i32 {
+        self.0 = self.0.wrapping_add(1);
+        self.0
+    }
+}
@@ -1,98 +1,368 @@
 use crate::{
+    action::Action,
     board::Board,
     brick::Brick,
     cell::Cell,
+    error::TetrisError,
+    event::Event,
+    generator::PieceGenerator,
+    piece_set::PieceSet,
+    placement::{self, Placement},
     player::Player,
+    ruleset::{HoldRule, Ruleset},
+    stats::Stats,
     traits::{HasSize, IterateDimensions, Randomizer},
 };
 
-const EXTRA_FRAMES: u32 = 2;
-
 #[derive(Debug, Default, Clone)]
 pub struct Tetris<R> {
     board: Board,
     player: Player,
     ghost: Player,
-    next_player: Brick,
+    queue: Vec<Brick>,
     held: Option<Brick>,
-    step_timer: u32,
+    hold_used: bool,
+    /// Rows of gravity built up since the piece last fell.
+    fall: f32,
+    lock_timer: u32,
+    lock_resets: u32,
+    lowest_row: i32,
     score: u32,
+    lines: u32,
+    combo: Option<u32>,
+    locked: bool,
+    pieces_locked: u32,
+    /// Whether the last thing that moved the piece in play was a rotation.
+    last_rotated: bool,
+    /// The piece locked last was a T-spin, its lines are not cleared yet.
+    t_spin: bool,
+    stats: Stats,
+    /// The queue was given up front and is not refilled, the game ends when it runs out.
+    fixed_queue: bool,
+    /// Frames left until the next piece spawns, no piece is in play meanwhile.
+    entry_delay: u32,
+    /// Frames left until full lines are removed.
+    clear_delay: u32,
+    /// Rotation buffered during the entry delay, applied when the piece spawns.
+    initial_rotation: Option<bool>,
+    initial_hold: bool,
+    /// Ticks since the game started.
+    frame: u32,
+    game_over: bool,
+    events: Vec<Event>,
+    ruleset: Ruleset,
+    pieces: PieceSet,
+    generator: PieceGenerator,
     randomizer: R,
 }
 
 impl<R: Randomizer> Tetris<R> {
-    pub fn new(w: i32, h: i32, mut randomizer: R) -> Self {
-        Self {
-            board: Board::new(w, h),
-            player: Player::with_brick_centered_rand(w, randomizer.next()),
-            next_player: Brick::by_index(randomizer.next()),
-            step_timer: 1,
+    pub fn new(w: i32, h: i32, pieces: PieceSet, randomizer: R) -> Result<Self, TetrisError> {
+        Self::with_ruleset(w, h, Ruleset::default(), pieces, randomizer)
+    }
+
+    /// A game on an empty board of `w` by `h`, if [`Ruleset::check_board`] accepts the size.
+    pub fn with_ruleset(
+        w: i32,
+        h: i32,
+        ruleset: Ruleset,
+        pieces: PieceSet,
+        randomizer: R,
+    ) -> Result<Self, TetrisError> {
+        ruleset.check_board(w, h, &pieces)?;
+        let mut tetris = Self {
+            board: Board::with_buffer(w, h, ruleset.buffer_rows as i32),
+            generator: PieceGenerator::new(ruleset.generator),
+            ruleset,
+            pieces,
+            randomizer,
+            ..Default::default()
+        };
+        tetris.spawn_next();
+        Ok(tetris)
+    }
+
+    /// A game starting on `board` that plays exactly the pieces of `queue`, in order. It
+    /// ends once they and the held piece, if any, are played.
+    pub fn with_setup(
+        board: Board,
+        queue: Vec<Brick>,
+        ruleset: Ruleset,
+        pieces: PieceSet,
+        randomizer: R,
+    ) -> Result<Self, TetrisError> {
+        ruleset.check_board_with_buffer(board.width(), board.height(), board.buffer(), &pieces)?;
+        let mut tetris = Self {
+            board,
+            queue,
+            fixed_queue: true,
+            generator: PieceGenerator::new(ruleset.generator),
+            ruleset,
+            pieces,
             randomizer,
             ..Default::default()
+        };
+        tetris.spawn_next();
+        Ok(tetris)
+    }
+
+    /// Applies `action`, returning whether it did anything.
+    pub fn apply(&mut self, action: Action) -> bool {
+        match action {
+            Action::MoveLeft => self.move_left(),
+            Action::MoveRight => self.move_right(),
+            Action::ShiftLeft => {
+                let moved = self.move_left();
+                while self.move_left() {}
+                moved
+            }
+            Action::ShiftRight => {
+                let moved = self.move_right();
+                while self.move_right() {}
+                moved
+            }
+            Action::SoftDrop => self.move_down(),
+            Action::HardDrop => {
+                let dropped = !self.game_over && !self.spawning();
+                self.drop_block();
+                dropped
+            }
+            Action::RotateLeft => self.rotate_left(),
+            Action::RotateRight => self.rotate_right(),
+            Action::Hold => self.swap_held(),
         }
     }
+
     pub fn move_left(&mut self) -> bool {
-        let moved = self.player.move_left();
-        if moved.brick_fits(&self.board) {
-            self.player = moved;
-            self.step_timer = self.step_timer.saturating_sub(EXTRA_FRAMES).max(1);
-            true
-        } else {
-            false
-        }
+        self.try_move(self.player.move_left())
     }
     pub fn move_right(&mut self) -> bool {
-        let moved = self.player.move_right();
-        if moved.brick_fits(&self.board) {
-            self.player = moved;
-            self.step_timer = self.step_timer.saturating_sub(EXTRA_FRAMES).max(1);
-            true
-        } else {
-            false
-        }
+        self.try_move(self.player.move_right())
     }
     pub fn move_down(&mut self) -> bool {
+        if self.game_over || self.spawning() {
+            return false;
+        }
         let moved = self.player.move_down();
         if moved.brick_fits(&self.board) {
             self.player = moved;
-            self.step_timer = 1;
-            self.score += 1;
+            self.fall = 0.0;
+            self.last_rotated = false;
+            self.score += self.ruleset.scoring.soft_drop;
+            self.reached_row();
             true
         } else {
-            self.board
-                .insert_brick(self.player.position(), self.player.brick());
-            self.player = Player::with_brick_centered(self.next_player, self.width());
-            self.next_player = self.random_brick();
-            self.score += 2;
+            self.lock(self.player);
             false
         }
     }
     pub fn rotate_left(&mut self) -> bool {
-        let moved = self.player.rotate_left();
-        if moved.brick_fits(&self.board) {
-            self.player = moved;
-            self.step_timer = self.step_timer.saturating_sub(EXTRA_FRAMES).max(1);
-            true
-        } else {
-            false
-        }
+        self.rotate(false)
     }
     pub fn rotate_right(&mut self) -> bool {
-        let moved = self.player.rotate_right();
-        if moved.brick_fits(&self.board) {
-            self.player = moved;
-            self.step_timer = self.step_timer.saturating_sub(EXTRA_FRAMES).max(1);
-            true
+        self.rotate(true)
+    }
+
+    /// Tries the rotated piece at every kick offset of its piece, keeping the first that fits.
+    fn rotate(&mut self, clockwise: bool) -> bool {
+        if self.game_over {
+            return false;
+        }
+        if self.spawning() {
+            self.initial_rotation = Some(clockwise);
+            return true;
+        }
+        let action = if clockwise {
+            Action::RotateRight
         } else {
-            false
+            Action::RotateLeft
+        };
+        match placement::step(
+            &self.board,
+            &self.player,
+            action,
+            &self.ruleset,
+            &self.pieces,
+        ) {
+            Some(rotated) => {
+                self.player = rotated;
+                self.moved();
+                self.last_rotated = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn try_move(&mut self, moved: Player) -> bool {
+        if self.game_over || self.spawning() || !moved.brick_fits(&self.board) {
+            return false;
+        }
+        self.player = moved;
+        self.moved();
+        self.last_rotated = false;
+        true
+    }
+
+    /// Restarts the lock delay of a resting piece while it has resets left.
+    fn moved(&mut self) {
+        let resets_left = self
+            .ruleset
+            .lock_resets
+            .is_none_or(|max| self.lock_resets < max);
+        if self.lock_timer > 0 && resets_left {
+            self.lock_timer = 0;
+            self.lock_resets += 1;
+        }
+    }
+
+    /// Gives the lock delay back once the piece gets lower than it has been.
+    fn reached_row(&mut self) {
+        let y = self.player.position().1;
+        if y > self.lowest_row {
+            self.lowest_row = y;
+            self.lock_timer = 0;
+            self.lock_resets = 0;
         }
     }
 
     fn random_brick(&mut self) -> Brick {
-        Brick::by_index(self.randomizer.next())
+        let i = self.generator.next(self.pieces.len(), &mut self.randomizer);
+        self.pieces.piece(i).brick
+    }
+
+    fn lock(&mut self, player: Player) {
+        self.board.insert_brick(player.position(), player.brick());
+        self.events.push(Event::Locked(player.cells()));
+        self.locked = true;
+        self.pieces_locked += 1;
+        self.stats.pieces += 1;
+
+        // Locking entirely inside the hidden buffer tops out.
+        if player.cells().iter().all(|&(_, y)| y < 0) {
+            self.top_out();
+            return;
+        }
+
+        if self.last_rotated && self.is_t_spin(&player) {
+            let lines = self.board.full_lines().len() as u32;
+            self.events.push(Event::TSpin { lines });
+            self.stats.t_spins[(lines as usize).min(3)] += 1;
+            self.t_spin = true;
+        }
+
+        let garbage = self.ruleset.garbage;
+        if garbage.interval > 0 && self.pieces_locked.is_multiple_of(garbage.interval) {
+            self.add_garbage(garbage.rows);
+        }
+
+        self.clear_delay = if self.board.full_lines().is_empty() {
+            0
+        } else {
+            self.ruleset.line_clear_delay
+        };
+        self.entry_delay = self.ruleset.are + self.clear_delay;
+        if self.entry_delay == 0 {
+            self.spawn_next();
+        }
+    }
+
+    /// Three-corner rule: a T piece with at least three of the four cells diagonal to its
+    /// centre taken, walls and floor included.
+    fn is_t_spin(&self, player: &Player) -> bool {
+        let brick = player.brick();
+        if self.pieces.piece(brick.kind()).name != "T" || brick.width() != 3 {
+            return false;
+        }
+        let (x, y) = player.position();
+        let corners = [(0, 0), (2, 0), (0, 2), (2, 2)];
+        corners
+            .iter()
+            .filter(|&&(dx, dy)| self.board.is_taken((x + dx, y + dy)))
+            .count()
+            >= 3
+    }
+
+    fn spawn_player(&self, brick: Brick) -> Player {
+        let player = self.ruleset.spawn.player_for(&self.board, brick);
+        // Rise into the buffer when the spawn position is blocked.
+        self.raised_to_fit(player).unwrap_or(player)
+    }
+
+    /// `player` moved up as little as needed to fit, without leaving the buffer.
+    fn raised_to_fit(&self, player: Player) -> Option<Player> {
+        let (_, y) = player.position();
+        (0..=(y + self.board.buffer()).max(0))
+            .map(|dy| player.moved_by((0, -dy)))
+            .find(|p| p.brick_fits(&self.board))
+    }
+
+    /// Puts `player` in play with fresh gravity and lock delay.
+    fn enter(&mut self, player: Player) {
+        self.player = player;
+        self.fall = 0.0;
+        self.lock_timer = 0;
+        self.lock_resets = 0;
+        self.lowest_row = player.position().1;
+        self.last_rotated = false;
+    }
+
+    fn spawn_next(&mut self) {
+        let brick = if self.fixed_queue && self.queue.is_empty() {
+            // The held piece is the last one left to play.
+            let Some(held) = self.held.take() else {
+                // Out of pieces: settle the last clear and end the game.
+                self.clear_lines();
+                self.top_out();
+                return;
+            };
+            held
+        } else {
+            // The next piece is drawn even without previews, holding into an empty slot
+            // needs it.
+            let previews = self.ruleset.preview.max(1);
+            while !self.fixed_queue && self.queue.len() <= previews {
+                let brick = self.random_brick();
+                self.queue.push(brick);
+            }
+            self.queue.remove(0)
+        };
+        self.enter(self.spawn_player(brick));
+        self.hold_used = false;
+
+        // Initial hold and rotation, pressed while waiting for this piece.
+        if std::mem::take(&mut self.initial_hold) {
+            self.swap_held();
+        }
+        if let Some(clockwise) = self.initial_rotation.take() {
+            self.rotate(clockwise);
+        }
+    }
+
+    /// Pushes `rows` rows of garbage, each with one random hole, under the stack.
+    pub fn add_garbage(&mut self, rows: u32) {
+        for _ in 0..rows {
+            let hole = self.randomizer.next().rem_euclid(self.width());
+            if !self.board.push_garbage(hole) {
+                self.top_out();
+                return;
+            }
+        }
+        // Keep the piece in play above the rows that came up under it.
+        if let Some(raised) = self.raised_to_fit(self.player) {
+            self.player = raised;
+        }
+    }
+
+    fn top_out(&mut self) {
+        self.game_over = true;
+        self.events.push(Event::GameOver);
     }
 
     pub fn drop_block(&mut self) {
+        if self.game_over || self.spawning() {
+            return;
+        }
         let mut dropped = self.player;
         'dropped: loop {
             let lower = dropped.move_down();
@@ -102,39 +372,95 @@ impl<R: Randomizer> Tetris<R> {
                 break 'dropped;
             }
         }
-        self.board.insert_brick(dropped.position(), dropped.brick());
-        self.player = Player::with_brick_centered(self.next_player, self.width());
-        self.next_player = self.random_brick();
-        self.step_timer = 1;
-        self.score += 1;
+        let distance = dropped.position().1 - self.player.position().1;
+        if distance > 0 {
+            self.last_rotated = false;
+        }
+        self.events.push(Event::HardDropped {
+            cells: dropped.cells(),
+            distance,
+        });
+        self.lock(dropped);
+        self.score += self.ruleset.scoring.hard_drop * distance as u32;
     }
 
     pub fn swap_held(&mut self) -> bool {
-        if let Some(h) = self.held {
-            let changed = self.player.set_brick(h);
-            if self.board.brick_fits(changed.position(), changed.brick()) {
-                let current = self.player.brick();
+        if self.game_over {
+            return false;
+        }
+        if self.ruleset.hold == HoldRule::Disabled {
+            return false;
+        }
+        if self.spawning() {
+            self.initial_hold = true;
+            return true;
+        }
+        if self.ruleset.hold == HoldRule::OncePerPiece && self.hold_used {
+            return false;
+        }
+
+        // Pieces go into hold in their spawn orientation.
+        let current = self.pieces.piece(self.player.brick().kind()).brick;
+        match self.held {
+            Some(h) if self.ruleset.hold == HoldRule::Unlimited => {
+                let changed = self.player.set_brick(h);
+                if !changed.brick_fits(&self.board) {
+                    return false;
+                }
                 self.held = Some(current);
                 self.player = changed;
-                return true;
             }
-        } else {
-            self.held = Some(self.player.brick());
-            self.player = Player::with_brick_centered(self.next_player, self.width());
-            self.next_player = self.random_brick();
-            self.step_timer -= self.step_timer.saturating_sub(EXTRA_FRAMES).max(1);
-            return true;
+            Some(h) => {
+                self.held = Some(current);
+                self.enter(self.spawn_player(h));
+            }
+            None if self.fixed_queue && self.queue.is_empty() => return false,
+            None => {
+                self.held = Some(current);
+                self.spawn_next();
+            }
         }
-        false
+        self.hold_used = true;
+        self.stats.holds += 1;
+        true
     }
 
     pub fn tick(&mut self) {
-        if self.step_timer % 60 == 0 {
-            self.move_down();
+        if self.game_over {
+            return;
+        }
+        self.frame += 1;
+        self.stats.frames += 1;
+
+        if self.entry_delay > 0 {
+            self.clear_delay = self.clear_delay.saturating_sub(1);
+            if self.clear_delay == 0 {
+                self.clear_lines();
+            }
+            self.entry_delay -= 1;
+            if self.entry_delay > 0 {
+                return;
+            }
+            self.spawn_next();
+        } else {
+            self.fall_and_lock();
+            if self.clear_delay == 0 {
+                self.clear_lines();
+            }
+            if self.entry_delay > 0 {
+                return;
+            }
         }
 
-        let removed = self.board.clean_drop();
-        self.score += removed * removed.pow(3);
+        // Checked after clearing, so a piece spawned onto a row that was just
+        // completed does not count as a top out.
+        if self.game_over {
+            return;
+        }
+        if !self.player.brick_fits(&self.board) {
+            self.top_out();
+            return;
+        }
 
         let mut ghost = self.player;
         'ghost: loop {
@@ -147,20 +473,161 @@ impl<R: Randomizer> Tetris<R> {
         }
 
         self.ghost = ghost;
-        self.step_timer += 1;
+    }
+
+    /// Applies gravity and the lock delay to the piece in play.
+    fn fall_and_lock(&mut self) {
+        self.fall += self.ruleset.gravity(self.level());
+        let mut blocked = false;
+        while self.fall >= 1.0 {
+            self.fall -= 1.0;
+            let lower = self.player.move_down();
+            if lower.brick_fits(&self.board) {
+                self.player = lower;
+                self.last_rotated = false;
+                self.reached_row();
+            } else {
+                self.fall = 0.0;
+                blocked = true;
+                break;
+            }
+        }
+
+        let resting = !self.player.move_down().brick_fits(&self.board);
+        if resting && !self.locked {
+            if self.ruleset.lock_delay == 0 {
+                if blocked {
+                    self.lock(self.player);
+                }
+            } else {
+                self.lock_timer += 1;
+                if self.lock_timer >= self.ruleset.lock_delay {
+                    self.lock(self.player);
+                }
+            }
+        }
+    }
+
+    /// Removes full lines, scoring them and keeping track of the combo.
+    fn clear_lines(&mut self) {
+        let rows = self.board.clean_drop();
+        let removed = rows.len() as u32;
+        if removed > 0 {
+            let combo = self.combo.map_or(0, |c| c + 1);
+            self.stats.cleared(removed, self.t_spin, combo);
+            let level = self.level();
+            let scoring = &self.ruleset.scoring;
+            let multiplier = if scoring.level_multiplier { level } else { 1 };
+            self.score += (scoring.line_clear(removed) + scoring.combo * combo) * multiplier;
+            self.combo = Some(combo);
+            self.lines += removed;
+            self.events.push(Event::LinesCleared { rows, combo });
+            if self.level() > level {
+                self.events.push(Event::LevelUp(self.level()));
+            }
+        } else if self.locked {
+            self.combo = None;
+        }
+        self.locked = false;
+        self.t_spin = false;
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    pub fn ghost(&self) -> Player {
+        self.ghost
     }
 
     pub fn next(&self) -> Brick {
-        self.next_player
+        self.queue.first().copied().unwrap_or_default()
+    }
+
+    /// The upcoming pieces, as many as the ruleset previews.
+    pub fn queue(&self) -> &[Brick] {
+        &self.queue[..self.ruleset.preview.min(self.queue.len())]
+    }
+
+    /// Everywhere the piece in play can lock from where it is now, with the inputs to get there.
+    pub fn placements(&self) -> Vec<Placement> {
+        if self.game_over || self.spawning() {
+            return vec![];
+        }
+        placement::placements(&self.board, self.player, &self.ruleset, &self.pieces)
     }
 
     pub fn held(&self) -> Option<Brick> {
         self.held
     }
 
+    /// Whether a hold would be accepted right now.
+    pub fn can_hold(&self) -> bool {
+        match self.ruleset.hold {
+            HoldRule::Disabled => false,
+            HoldRule::OncePerPiece => !self.hold_used,
+            HoldRule::Unlimited => true,
+        }
+    }
+
+    pub fn pieces(&self) -> &PieceSet {
+        &self.pieces
+    }
+
+    pub fn ruleset(&self) -> &Ruleset {
+        &self.ruleset
+    }
+
     pub fn score(&self) -> u32 {
         self.score
     }
+
+    pub fn lines(&self) -> u32 {
+        self.lines
+    }
+
+    /// Starts at 1 and goes up every `lines_per_level` cleared lines.
+    pub fn level(&self) -> u32 {
+        self.lines / self.ruleset.lines_per_level.max(1) + 1
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    /// Adds `keys` newly pressed keys to the stats. Only the frontend tells a press from an
+    /// input repeated while its key is held, so it counts them.
+    pub fn count_keys(&mut self, keys: u32) {
+        self.stats.keys += keys;
+    }
+
+    pub fn pieces_locked(&self) -> u32 {
+        self.pieces_locked
+    }
+
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// Whether the game waits between two pieces, with no piece in play.
+    pub fn spawning(&self) -> bool {
+        self.entry_delay > 0
+    }
+
+    /// Takes the events that happened since the last call.
+    pub fn drain_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Whether the stack has topped out, i.e. a new piece could not spawn, or a fixed queue
+    /// has run out.
+    pub fn game_over(&self) -> bool {
+        self.game_over
+    }
 }
 
 impl<R> HasSize for Tetris<R> {
@@ -182,8 +649,10 @@ impl<R> IterateDimensions for Tetris<R> {
 
     fn iter_dim(&self, mut action: impl FnMut(i32, i32, Self::Output)) {
         let mut board = self.board.clone();
-        board.insert_brick(self.ghost.position(), self.ghost.brick());
-        board.insert_brick(self.player.position(), self.player.brick());
+        if self.entry_delay == 0 {
+            board.insert_brick(self.ghost.position(), self.ghost.brick());
+            board.insert_brick(self.player.position(), self.player.brick());
+        }
 
         for y in 0..self.height() {
             for x in 0..self.width() {
@@ -0,0 +1,88 @@
+use tetris_core::{
+    board::Board,
+    brick::Brick,
+    cell::Cell,
+    piece_set::PieceSet,
+    traits::{HasSize, IterateDimensions},
+};
+
+/// Collision the way it is defined: every cell of the brick inside the board and empty.
+fn fits_cell_by_cell(board: &Board, (ox, oy): (i32, i32), brick: Brick) -> bool {
+    let mut fits = true;
+    brick.iter_dim(|x, y, c| {
+        let at = (ox + x, oy + y);
+        if c.is_some() && (!board.contains(at) || board[at].is_some()) {
+            fits = false;
+        }
+    });
+    fits
+}
+
+fn stack(width: i32) -> Board {
+    let mut board = Board::with_buffer(width, 12, 2);
+    for y in 4..12 {
+        for x in 0..width {
+            if (x * 7 + y * 3) % 5 < 2 + y / 5 {
+                board.set_field((x, y), Some(Cell::Garbage));
+            }
+        }
+    }
+    board
+}
+
+#[test]
+fn fit_tests_match_the_cells() {
+    let sets = [
+        PieceSet::tetrominoes(),
+        PieceSet::pentominoes(),
+        PieceSet::tetrominoes().scaled(2),
+    ];
+    for width in [4, 10, 63, 64, 65, 128] {
+        let board = stack(width);
+        for set in &sets {
+            for piece in set.pieces() {
+                let mut brick = piece.brick;
+                for _ in 0..4 {
+                    for y in -5..14 {
+                        for x in -9..width + 2 {
+                            assert_eq!(
+                                board.brick_fits((x, y), brick),
+                                fits_cell_by_cell(&board, (x, y), brick),
+                                "{} at ({x}, {y}) on a board {width} wide",
+                                piece.name
+                            );
+                        }
+                    }
+                    brick = brick.rotate_right();
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn rows_follow_the_cells() {
+    for width in [4, 10, 128] {
+        let mut board = stack(width);
+        board.set_field((0, 11), None);
+        board.push_garbage(1);
+        board.drop_line(5);
+        board.clear_line(7);
+        for y in [0, 1, 2, 3].into_iter().chain([8, 9, 10, 11]) {
+            for x in 0..width {
+                board.set_field((x, y), Some(Cell::Garbage));
+            }
+        }
+        board.clean_drop();
+        for y in -board.buffer()..board.height() {
+            let mut expected = 0;
+            for x in 0..width {
+                if board[(x, y)].is_some() {
+                    expected |= 1 << x;
+                }
+            }
+            assert_eq!(board.row(y), expected, "row {y} of a board {width} wide");
+            assert_eq!(board.line_full(y), expected.count_ones() == width as u32);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use common::Counter;
+use tetris_core::{
+    board::Board,
+    brick::Brick,
+    cell::Cell,
+    error::TetrisError,
+    piece_set::PieceSet,
+    ruleset::{Ruleset, Spawn},
+    tetris::Tetris,
+    traits::HasSize,
+};
+
+#[test]
+fn cells_outside_the_board_are_none() {
+    let board = Board::from_rows(&["#...", "...#"], 1);
+    assert!(board.get((0, 0)).unwrap().is_some());
+    assert!(board.get((3, 1)).unwrap().is_some());
+    assert!(board.get((1, -1)).unwrap().is_none());
+    // Past the right wall used to read the start of the next row.
+    for position in [
+        (4, 0),
+        (-1, 1),
+        (0, -2),
+        (0, 2),
+        (i32::MAX, 0),
+        (0, i32::MIN),
+    ] {
+        assert!(board.get(position).is_none(), "{position:?}");
+    }
+}
+
+#[test]
+fn changes_through_get_mut_keep_the_rows_in_step() {
+    let mut board = Board::with_buffer(4, 2, 1);
+    *board.get_mut((2, 1)).unwrap() = Some(Cell::Garbage);
+    *board.get_mut((0, -1)).unwrap() = Some(Cell::Garbage);
+    assert_eq!(board.row(1), 0b0100);
+    assert_eq!(board.row(-1), 0b0001);
+    assert!(board.is_taken((2, 1)));
+
+    board.get_mut((2, 1)).unwrap().take();
+    assert_eq!(board.row(1), 0);
+    assert!(board.get_mut((4, 1)).is_none());
+    assert!(board.get_mut((0, -2)).is_none());
+}
+
+#[test]
+#[should_panic(expected = "outside the board")]
+fn indexing_past_the_wall_panics() {
+    let board = Board::new(4, 2);
+    let _ = board[(4, 0)];
+}
+
+#[test]
+fn invalid_sizes_are_errors() {
+    assert_eq!(
+        Board::try_with_buffer(0, 20, 0).unwrap_err(),
+        TetrisError::InvalidWidth(0)
+    );
+    assert_eq!(
+        Board::try_with_buffer(10, -1, 0).unwrap_err(),
+        TetrisError::InvalidHeight(-1)
+    );
+    assert_eq!(
+        Board::try_with_buffer(10, 20, -2).unwrap_err(),
+        TetrisError::InvalidBuffer(-2)
+    );
+    assert_eq!(Board::try_with_buffer(10, 20, 2).unwrap().height(), 20);
+    assert_eq!(
+        Board::try_new(10, 0).unwrap_err(),
+        TetrisError::InvalidHeight(0)
+    );
+    assert_eq!(Board::try_new(10, 20).unwrap().buffer(), 0);
+
+    let error = Tetris::new(-5, 20, PieceSet::tetrominoes(), Counter::default()).unwrap_err();
+    assert_eq!(error, TetrisError::InvalidWidth(-5));
+    assert!(Tetris::new(10, 20, PieceSet::tetrominoes(), Counter::default()).is_ok());
+}
+
+#[test]
+fn bricks_are_only_inserted_where_they_fit() {
+    let mut board = Board::from_rows(&["....", "....", "#..."], 0);
+    let o = Brick::o();
+    let error = board.try_insert_brick((-1, 0), o).unwrap_err();
+    assert_eq!(error, TetrisError::InvalidPlacement { position: (-1, 0) });
+    assert!(board.try_insert_brick((3, 0), o).is_err());
+    assert!(board.try_insert_brick((0, 1), o).is_err());
+    assert_eq!(board.row(1), 0);
+
+    board.try_insert_brick((1, 1), o).unwrap();
+    assert_eq!(board.row(1), 0b0110);
+    assert!(board.try_insert_brick((1, 1), o).is_err());
+}
+
+#[test]
+fn setups_are_checked_against_the_buffer_of_their_board() {
+    let ruleset = Ruleset {
+        spawn: Spawn {
+            column: None,
+            row: Some(-2),
+        },
+        ..Ruleset::guideline()
+    };
+    let setup = |buffer| {
+        let board = Board::with_buffer(10, 20, buffer);
+        let queue = vec![Brick::t()];
+        Tetris::with_setup(
+            board,
+            queue,
+            ruleset.clone(),
+            PieceSet::tetrominoes(),
+            Counter::default(),
+        )
+    };
+    // The ruleset's 20 buffer rows would hold the spawn, the board has none.
+    assert!(matches!(
+        setup(0).unwrap_err(),
+        TetrisError::SpawnOutside { buffer: 0, .. }
+    ));
+    assert!(setup(2).is_ok());
+}
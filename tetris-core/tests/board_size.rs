@@ -0,0 +1,145 @@
+mod common;
+
+use common::Counter;
+use tetris_core::{
+    action::Action,
+    board::Board,
+    brick::Brick,
+    error::TetrisError,
+    piece_set::PieceSet,
+    ruleset::{Ruleset, Spawn},
+    tetris::Tetris,
+    traits::HasSize,
+};
+
+#[test]
+fn guideline_spawn_columns_on_ten_wide() {
+    let spawn = Spawn::default();
+    for piece in PieceSet::tetrominoes().pieces() {
+        let column = spawn.column_for(10, &piece.brick);
+        let expected = match piece.name.as_str() {
+            "O" => 4,
+            _ => 3,
+        };
+        assert_eq!(
+            column, expected,
+            "{} spawns in the wrong column",
+            piece.name
+        );
+    }
+}
+
+#[test]
+fn spawn_rounds_to_the_left_on_odd_widths() {
+    let spawn = Spawn::default();
+    let pieces = PieceSet::tetrominoes();
+    let i = &pieces.pieces()[0].brick;
+    assert_eq!(spawn.column_for(9, i), 2);
+    assert_eq!(spawn.column_for(4, i), 0);
+}
+
+#[test]
+fn pieces_spawn_above_the_field_when_there_is_a_buffer() {
+    let spawn = Spawn::default();
+    assert_eq!(spawn.row_for(20), -2);
+    assert_eq!(spawn.row_for(1), -1);
+    assert_eq!(spawn.row_for(0), 0);
+
+    // T: two rows above the field, then down a row so its flat side shows in the top row.
+    let t = Brick::t();
+    let board = Board::with_buffer(10, 20, 20);
+    assert_eq!(spawn.player_for(&board, t).position(), (3, -1));
+    let blocked = Board::from_rows(&[".....#...."], 20);
+    assert_eq!(spawn.player_for(&blocked, t).position(), (3, -2));
+    let set = Spawn {
+        row: Some(-2),
+        ..spawn
+    };
+    assert_eq!(set.player_for(&board, t).position(), (3, -2));
+}
+
+#[test]
+fn boards_too_narrow_for_a_piece_are_rejected() {
+    let ruleset = Ruleset::guideline();
+    let error = ruleset
+        .check_board(3, 20, &PieceSet::tetrominoes())
+        .unwrap_err();
+    assert!(
+        matches!(&error, TetrisError::PieceTooLarge { piece, .. } if piece == "I"),
+        "{error}"
+    );
+    assert!(error.to_string().contains("I piece"), "{error}");
+
+    assert!(ruleset.check_board(4, 20, &PieceSet::tetrominoes()).is_ok());
+    assert!(ruleset
+        .check_board(4, 20, &PieceSet::pentominoes())
+        .is_err());
+    assert_eq!(
+        ruleset.check_board(0, 20, &PieceSet::tetrominoes()),
+        Err(TetrisError::InvalidWidth(0))
+    );
+    assert_eq!(
+        ruleset.check_board(10, 0, &PieceSet::tetrominoes()),
+        Err(TetrisError::InvalidHeight(0))
+    );
+}
+
+#[test]
+fn spawn_outside_the_board_is_rejected() {
+    let ruleset = Ruleset {
+        spawn: Spawn {
+            column: Some(8),
+            row: Some(0),
+        },
+        ..Ruleset::guideline()
+    };
+    let error = ruleset
+        .check_board(10, 20, &PieceSet::tetrominoes())
+        .unwrap_err();
+    assert!(
+        matches!(
+            error,
+            TetrisError::SpawnOutside {
+                position: (8, 0),
+                ..
+            }
+        ),
+        "{error}"
+    );
+    assert!(error.to_string().contains("column 8"), "{error}");
+
+    let ruleset = Ruleset {
+        spawn: Spawn {
+            column: None,
+            row: Some(-3),
+        },
+        buffer_rows: 2,
+        ..Ruleset::guideline()
+    };
+    assert!(ruleset
+        .check_board(10, 20, &PieceSet::tetrominoes())
+        .is_err());
+}
+
+#[test]
+fn very_wide_big_boards_play() {
+    let ruleset = Ruleset::guideline();
+    let big = PieceSet::tetrominoes().scaled(2);
+    assert!(ruleset.check_board(6, 40, &big).is_err());
+    assert!(ruleset.check_board(128, 40, &big).is_ok());
+    assert!(ruleset.check_board(129, 40, &big).is_err());
+
+    let mut tetris = Tetris::with_ruleset(128, 40, ruleset, big, Counter::default()).unwrap();
+    for _ in 0..10 {
+        let (x, _) = tetris.player().position();
+        let size = tetris.player().brick().width();
+        assert_eq!(x, (128 - size) / 2);
+        tetris.apply(Action::HardDrop);
+        while tetris.spawning() {
+            tetris.tick();
+        }
+        tetris.tick();
+    }
+    assert!(!tetris.game_over());
+    assert_eq!(tetris.pieces_locked(), 10);
+}
@@ -0,0 +1,119 @@
+use proptest::prelude::*;
+use tetris_core::{
+    board::Board,
+    cell::Cell,
+    palette::Palette,
+    traits::{HasSize, IterateDimensions},
+};
+
+/// Rows from the top of the buffer down, each cell empty or the colour of a piece.
+type Rows = Vec<Vec<Option<usize>>>;
+
+fn cell(piece: usize) -> Cell {
+    Cell::Normal {
+        kind: piece,
+        color: Palette::GUIDELINE.pieces[piece],
+    }
+}
+
+fn board(rows: &Rows, buffer: i32) -> Board {
+    let width = rows[0].len() as i32;
+    let mut board = Board::with_buffer(width, rows.len() as i32 - buffer, buffer);
+    for (y, row) in rows.iter().enumerate() {
+        for (x, &piece) in row.iter().enumerate() {
+            board.set_field((x as i32, y as i32 - buffer), piece.map(cell));
+        }
+    }
+    board
+}
+
+/// The rows of `board` in the form they were generated in. Cells have no equality, their
+/// debug output tells them apart.
+fn contents(board: &Board) -> Vec<Vec<String>> {
+    (-board.buffer()..board.height())
+        .map(|y| {
+            (0..board.width())
+                .map(|x| format!("{:?}", board.get_item(x, y)))
+                .collect()
+        })
+        .collect()
+}
+
+/// The obvious way: keep the rows that are not full and put empty ones on top.
+fn naive_clear(rows: &Rows, buffer: i32) -> (Rows, Vec<i32>) {
+    let full: Vec<i32> = (0..rows.len())
+        .filter(|&y| rows[y].iter().all(Option::is_some))
+        .map(|y| y as i32 - buffer)
+        .collect();
+    let kept: Rows = rows
+        .iter()
+        .filter(|row| !row.iter().all(Option::is_some))
+        .cloned()
+        .collect();
+    let mut cleared = vec![vec![None; rows[0].len()]; rows.len() - kept.len()];
+    cleared.extend(kept);
+    (cleared, full)
+}
+
+/// Boards of up to a little over 64 columns with plenty of full rows, many of them next to
+/// each other.
+fn rows() -> impl Strategy<Value = (Rows, i32)> {
+    (1usize..70, 1usize..24, 0i32..4).prop_flat_map(|(width, height, buffer)| {
+        let row = prop_oneof![
+            2 => Just(None::<usize>),
+            3 => (0usize..7).prop_map(Some),
+        ];
+        let full = proptest::collection::vec((0usize..7).prop_map(Some), width);
+        let any = proptest::collection::vec(row, width);
+        let line = prop_oneof![1 => full, 1 => any];
+        (
+            proptest::collection::vec(line, height + buffer as usize),
+            Just(buffer),
+        )
+    })
+}
+
+proptest! {
+    #[test]
+    fn clears_like_the_naive_way((rows, buffer) in rows()) {
+        let mut cleared = board(&rows, buffer);
+        let removed = cleared.clean_drop();
+
+        let (expected, full) = naive_clear(&rows, buffer);
+        prop_assert_eq!(removed, full);
+        prop_assert_eq!(contents(&cleared), contents(&board(&expected, buffer)));
+        prop_assert!(cleared.full_lines().is_empty());
+    }
+
+    #[test]
+    fn keeps_the_row_masks_in_step((rows, buffer) in rows()) {
+        let mut board = board(&rows, buffer);
+        board.clean_drop();
+        for y in -board.buffer()..board.height() {
+            let expected = (0..board.width())
+                .filter(|&x| board[(x, y)].is_some())
+                .fold(0u128, |mask, x| mask | 1 << x);
+            prop_assert_eq!(board.row(y), expected);
+        }
+    }
+}
+
+#[test]
+fn adjacent_full_rows_clear_together() {
+    let mut board = Board::from_rows(
+        &[
+            "..........",
+            "#.........",
+            "##########",
+            "##########",
+            ".#########",
+            "##########",
+        ],
+        0,
+    );
+    assert_eq!(board.clean_drop(), vec![2, 3, 5]);
+    assert_eq!(
+        board.to_string(),
+        "..........\n..........\n..........\n..........\nX.........\n.XXXXXXXXX\n"
+    );
+}
@@ -0,0 +1,162 @@
+//! Pieces shared by the integration tests: the predictable randomizer and proptest strategies
+//! for boards, bricks and games.
+#![allow(dead_code)]
+
+use proptest::prelude::*;
+use tetris_core::{
+    action::Action,
+    board::Board,
+    brick::{Brick, MAX_SIZE},
+    cell::Cell,
+    piece_set::PieceSet,
+    ruleset::Ruleset,
+    tetris::Tetris,
+    traits::{HasSize, Randomizer},
+};
+
+pub use tetris_core::test_util::Counter;
+
+/// Something to do to a game in one go.
+#[derive(Debug, Clone, Copy)]
+pub enum Step {
+    Apply(Action),
+    /// Steps the game by this many frames.
+    Tick(u32),
+    /// Pushes up this many garbage rows and steps one frame.
+    Garbage(u32),
+}
+
+impl Step {
+    pub fn run<R: Randomizer>(self, tetris: &mut Tetris<R>) {
+        match self {
+            Step::Apply(action) => {
+                tetris.apply(action);
+            }
+            Step::Tick(frames) => {
+                for _ in 0..frames {
+                    tetris.tick();
+                }
+            }
+            Step::Garbage(rows) => {
+                tetris.add_garbage(rows);
+                tetris.tick();
+            }
+        }
+    }
+}
+
+pub const ACTIONS: [Action; 9] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::ShiftLeft,
+    Action::ShiftRight,
+    Action::SoftDrop,
+    Action::HardDrop,
+    Action::RotateLeft,
+    Action::RotateRight,
+    Action::Hold,
+];
+
+pub fn action() -> impl Strategy<Value = Action> {
+    proptest::sample::select(ACTIONS.to_vec())
+}
+
+/// Mostly inputs, with time passing and the odd garbage row in between. Hard drops come
+/// often enough for the stack to grow and lines to clear.
+pub fn steps(garbage: bool) -> impl Strategy<Value = Vec<Step>> {
+    let garbage_weight = if garbage { 1 } else { 0 };
+    let step = prop_oneof![
+        8 => action().prop_map(Step::Apply),
+        2 => Just(Step::Apply(Action::HardDrop)),
+        3 => (1u32..40).prop_map(Step::Tick),
+        garbage_weight => (1u32..4).prop_map(Step::Garbage),
+    ];
+    proptest::collection::vec(step, 0..200)
+}
+
+/// How a game is set up: the seed, the rules, the pieces and the size of the well.
+#[derive(Debug, Clone)]
+pub struct Setup {
+    pub seed: i32,
+    pub ruleset: Ruleset,
+    pub pieces: PieceSet,
+    pub size: (i32, i32),
+}
+
+impl Setup {
+    pub fn start(&self) -> Tetris<Counter> {
+        let (w, h) = self.size;
+        Tetris::with_ruleset(
+            w,
+            h,
+            self.ruleset.clone(),
+            self.pieces.clone(),
+            Counter::with_seed(self.seed),
+        )
+        .expect("setups are checked")
+    }
+}
+
+/// Games the rules accept, on wells from barely wide enough to wider than usual.
+pub fn setup() -> impl Strategy<Value = Setup> {
+    let presets = proptest::sample::select(Ruleset::PRESETS);
+    let pieces = prop_oneof![
+        4 => Just(PieceSet::tetrominoes()),
+        1 => Just(PieceSet::pentominoes()),
+        1 => Just(PieceSet::trominoes()),
+        1 => Just(PieceSet::tetrominoes().scaled(2)),
+    ];
+    (any::<i32>(), presets, pieces, 3i32..20, 4i32..26)
+        .prop_map(|(seed, preset, pieces, w, h)| Setup {
+            seed,
+            ruleset: Ruleset::preset(preset).expect("presets load"),
+            pieces,
+            size: (w, h),
+        })
+        .prop_filter("the board must fit the pieces", |s| {
+            s.ruleset.check_board(s.size.0, s.size.1, &s.pieces).is_ok()
+        })
+}
+
+/// Any board up to a little over 64 columns, buffer rows included.
+pub fn board() -> impl Strategy<Value = Board> {
+    (1i32..70, 1i32..24, 0i32..4).prop_flat_map(|(w, h, buffer)| {
+        let rows = proptest::collection::vec(any::<u128>(), (h + buffer) as usize);
+        // Some rows come completely full, so there is something to clear.
+        let full = proptest::collection::vec(any::<bool>(), (h + buffer) as usize);
+        (rows, full).prop_map(move |(rows, full)| {
+            let mut board = Board::with_buffer(w, h, buffer);
+            for (i, (&bits, &full)) in rows.iter().zip(&full).enumerate() {
+                for x in 0..w {
+                    if full || bits & (1 << x) != 0 {
+                        board.set_field((x, i as i32 - buffer), Some(Cell::Garbage));
+                    }
+                }
+            }
+            board
+        })
+    })
+}
+
+/// A brick of any shape in a box of any size.
+pub fn brick() -> impl Strategy<Value = Brick> {
+    (1..=MAX_SIZE as usize).prop_flat_map(|size| {
+        proptest::collection::vec(proptest::collection::vec(any::<bool>(), size), size).prop_map(
+            |rows| {
+                let rows: Vec<String> = rows
+                    .iter()
+                    .map(|row| row.iter().map(|&c| if c { '#' } else { '.' }).collect())
+                    .collect();
+                let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+                Brick::from_rows(0, &rows, Cell::Garbage)
+            },
+        )
+    })
+}
+
+/// Number of filled cells, buffer rows included.
+pub fn filled(board: &Board) -> u32 {
+    (-board.buffer()..board.height())
+        .map(|y| board.row(y).count_ones())
+        .sum()
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use common::{brick, filled, setup, steps, Setup, Step};
+use proptest::prelude::*;
+use tetris_core::{
+    action::Action,
+    event::Event,
+    piece_set::PieceSet,
+    player::Player,
+    ruleset::Ruleset,
+    tetris::Tetris,
+    traits::{HasSize, Randomizer},
+};
+
+/// What two runs of the same game must agree on.
+fn snapshot<R: Randomizer>(tetris: &Tetris<R>) -> String {
+    let queue: Vec<usize> = tetris.queue().iter().map(|b| b.kind()).collect();
+    format!(
+        "{}score {} lines {} frame {} queue {:?} held {:?} player {:?} over {}",
+        tetris.board(),
+        tetris.score(),
+        tetris.lines(),
+        tetris.frame(),
+        queue,
+        tetris.held().map(|b| b.kind()),
+        tetris.player(),
+        tetris.game_over(),
+    )
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(128))]
+
+    #[test]
+    fn the_piece_in_play_never_overlaps_the_stack(setup in setup(), steps in steps(true)) {
+        let mut tetris = setup.start();
+        for step in steps {
+            step.run(&mut tetris);
+            if tetris.game_over() {
+                break;
+            }
+            if !tetris.spawning() {
+                prop_assert!(
+                    tetris.player().brick_fits(tetris.board()),
+                    "{:?} overlaps after {:?}\n{}",
+                    tetris.player(),
+                    step,
+                    tetris.board()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cells_only_come_from_locks_and_go_with_clears(setup in setup(), steps in steps(false)) {
+        let mut tetris = setup.start();
+        let width = tetris.board().width() as u32;
+        let mut expected = filled(tetris.board());
+        for step in steps {
+            step.run(&mut tetris);
+            if tetris.game_over() {
+                break;
+            }
+            for event in tetris.drain_events() {
+                match event {
+                    Event::Locked(cells) => expected += cells.len() as u32,
+                    Event::LinesCleared { rows, .. } => expected -= width * rows.len() as u32,
+                    _ => {}
+                }
+            }
+            prop_assert_eq!(filled(tetris.board()), expected, "after {:?}", step);
+        }
+    }
+
+    #[test]
+    fn clean_drop_removes_a_full_width_per_row(mut board in common::board()) {
+        let before = filled(&board);
+        let rows = board.clean_drop();
+        prop_assert_eq!(
+            filled(&board),
+            before - board.width() as u32 * rows.len() as u32
+        );
+    }
+
+    #[test]
+    fn rotations_undo_each_other(brick in brick(), x in -4i32..12, y in -4i32..20) {
+        let back = brick.rotate_left().rotate_right();
+        prop_assert_eq!(back.rows(), brick.rows());
+        let around = brick.rotate_right().rotate_right().rotate_right().rotate_right();
+        prop_assert_eq!(around.rows(), brick.rows());
+        prop_assert_eq!(brick.rotate_right().count(), brick.count());
+
+        let player = Player::with_brick_at(brick, (x, y));
+        let back = player.rotate_right().rotate_left();
+        prop_assert_eq!(back.position(), player.position());
+        prop_assert_eq!(back.rotation(), player.rotation());
+        prop_assert_eq!(back.cells(), player.cells());
+    }
+
+    #[test]
+    fn the_same_seed_and_inputs_play_the_same_game(setup in setup(), steps in steps(true)) {
+        let mut first = setup.start();
+        let mut second = setup.start();
+        for step in steps {
+            step.run(&mut first);
+            step.run(&mut second);
+            prop_assert_eq!(snapshot(&first), snapshot(&second));
+            prop_assert_eq!(
+                format!("{:?}", first.drain_events()),
+                format!("{:?}", second.drain_events())
+            );
+        }
+    }
+}
+
+#[test]
+fn garbage_under_a_falling_piece_pushes_it_up() {
+    let mut tetris = Setup {
+        seed: 7,
+        ruleset: Ruleset::guideline(),
+        pieces: PieceSet::tetrominoes(),
+        size: (10, 20),
+    }
+    .start();
+    while tetris.spawning() {
+        tetris.tick();
+    }
+    // Soft dropping into the stack would lock the piece.
+    while tetris.player().move_down().brick_fits(tetris.board()) {
+        tetris.apply(Action::SoftDrop);
+    }
+    let resting = tetris.player().position();
+    Step::Garbage(2).run(&mut tetris);
+    assert!(!tetris.game_over());
+    assert_eq!(tetris.player().position().1, resting.1 - 2);
+    assert!(tetris.player().brick_fits(tetris.board()));
+}
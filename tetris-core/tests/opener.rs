@@ -0,0 +1,48 @@
+use tetris_core::{board::Board, cell::Cell, opener::Opener};
+
+/// Two bags on a four wide well: an I, then an O on top of it.
+fn stacked() -> Opener {
+    Opener {
+        name: "Stacked".to_owned(),
+        bags: vec![
+            vec!["IIII".to_owned()],
+            vec![".OO.".to_owned(), ".OO.".to_owned(), "IIII".to_owned()],
+        ],
+    }
+}
+
+#[test]
+fn later_bags_only_target_the_cells_they_add() {
+    let board = Board::new(4, 6);
+    let targets = stacked().targets(&board);
+    assert_eq!(
+        targets,
+        [
+            ("I".to_owned(), vec![(0, 5), (1, 5), (2, 5), (3, 5)]),
+            ("O".to_owned(), vec![(1, 3), (1, 4), (2, 3), (2, 4)]),
+        ]
+    );
+}
+
+#[test]
+fn the_bag_being_placed_is_the_first_not_built() {
+    let opener = stacked();
+    let mut board = Board::new(4, 6);
+    assert_eq!(opener.bag(&board), Some(0));
+    for x in 0..4 {
+        board.set_field((x, 5), Some(Cell::Garbage));
+    }
+    assert_eq!(opener.bag(&board), Some(1));
+    for cell in [(1, 3), (1, 4), (2, 3), (2, 4)] {
+        board.set_field(cell, Some(Cell::Garbage));
+    }
+    assert_eq!(opener.bag(&board), None);
+    assert!(opener.is_built(&board));
+}
+
+#[test]
+fn a_bag_that_drops_cells_of_the_one_before_is_refused() {
+    let mut opener = stacked();
+    opener.bags[1][2] = "III.".to_owned();
+    assert!(opener.targets(&Board::new(4, 6)).is_empty());
+}
@@ -0,0 +1,39 @@
+use tetris_core::{
+    cell::Cell,
+    piece_set::{Piece, PieceSet},
+};
+
+fn kind_of(cell: Cell) -> Option<usize> {
+    match cell {
+        Cell::Normal { kind, .. } => Some(kind),
+        _ => None,
+    }
+}
+
+#[test]
+fn cells_carry_the_kind_of_their_piece() {
+    for set in [
+        PieceSet::tetrominoes(),
+        PieceSet::pentominoes(),
+        PieceSet::trominoes(),
+    ] {
+        for (i, piece) in set.pieces().iter().enumerate() {
+            assert_eq!(piece.brick.kind(), i);
+            assert_eq!(kind_of(piece.brick.cell()), Some(i), "{}", piece.name);
+        }
+    }
+}
+
+#[test]
+fn renumbered_pieces_keep_kind_and_cells_together() {
+    let pentominoes = PieceSet::pentominoes();
+    // The pentomino at kind 9 shares its colour with the one at kind 2.
+    let picked: Vec<Piece> = [9, 2]
+        .iter()
+        .map(|&i| pentominoes.piece(i).clone())
+        .collect();
+    let set = PieceSet::new(picked);
+    assert_eq!(kind_of(set.piece(0).brick.cell()), Some(0));
+    assert_eq!(kind_of(set.piece(1).brick.cell()), Some(1));
+    assert_eq!(kind_of(set.piece(0).brick.as_ghost().cell()), None);
+}
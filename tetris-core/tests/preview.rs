@@ -0,0 +1,47 @@
+mod common;
+
+use common::Counter;
+use tetris_core::{
+    action::Action, piece_set::PieceSet, ruleset::Ruleset, tetris::Tetris, traits::Randomizer,
+};
+
+fn game(preview: usize) -> Tetris<Counter> {
+    let ruleset = Ruleset {
+        preview,
+        ..Ruleset::guideline()
+    };
+    let mut tetris = Tetris::with_ruleset(
+        10,
+        20,
+        ruleset,
+        PieceSet::tetrominoes(),
+        Counter::with_seed(3),
+    )
+    .unwrap();
+    while tetris.spawning() {
+        tetris.tick();
+    }
+    tetris
+}
+
+#[test]
+fn the_queue_shows_as_many_pieces_as_the_preview() {
+    assert_eq!(game(3).queue().len(), 3);
+    assert_eq!(game(1).queue().len(), 1);
+    assert!(game(0).queue().is_empty());
+}
+
+#[test]
+fn hold_works_without_a_preview() {
+    let mut hidden = game(0);
+    let mut shown = game(1);
+    let next = shown.queue()[0].kind();
+    assert!(hidden.apply(Action::Hold));
+    assert!(shown.apply(Action::Hold));
+    assert_eq!(hidden.player().brick().kind(), next);
+    assert_eq!(
+        hidden.held().map(|b| b.kind()),
+        shown.held().map(|b| b.kind())
+    );
+    assert!(hidden.queue().is_empty());
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::Counter;
+use tetris_core::{
+    action::Action,
+    error::{PuzzleError, TetrisError},
+    puzzle::{Goal, Puzzle},
+    tetris::Tetris,
+};
+
+fn puzzle(board: &[&str], pieces: &[&str]) -> Puzzle {
+    Puzzle {
+        name: "test".to_owned(),
+        board: board.iter().map(|&row| row.to_owned()).collect(),
+        pieces: pieces.iter().map(|&name| name.to_owned()).collect(),
+        goal: Goal::Lines(1),
+        hold: true,
+    }
+}
+
+fn start(puzzle: &Puzzle) -> Result<Tetris<Counter>, PuzzleError> {
+    puzzle.start(Counter::default()).map(|(tetris, _)| tetris)
+}
+
+fn name(tetris: &Tetris<Counter>) -> &str {
+    let kind = tetris.player().brick().kind();
+    &tetris.pieces().piece(kind).name
+}
+
+#[test]
+fn malformed_puzzles_are_errors() {
+    let row = "..........";
+    assert_eq!(
+        start(&puzzle(&[""], &["T"])).unwrap_err(),
+        PuzzleError::NoColumns
+    );
+    assert_eq!(
+        start(&puzzle(&[row, "..."], &["T"])).unwrap_err(),
+        PuzzleError::UnevenRow {
+            row: "...".to_owned(),
+            width: 10
+        }
+    );
+    assert_eq!(
+        start(&puzzle(&["....x....."], &["T"])).unwrap_err(),
+        PuzzleError::UnexpectedCell('x')
+    );
+    assert_eq!(
+        start(&puzzle(&[row], &["T", "Q"])).unwrap_err(),
+        PuzzleError::UnknownPiece("Q".to_owned())
+    );
+    assert_eq!(
+        start(&puzzle(&[row], &[])).unwrap_err(),
+        PuzzleError::NoPieces
+    );
+    let error = start(&puzzle(&[".."], &["O"])).unwrap_err();
+    assert!(
+        matches!(error, PuzzleError::Game(TetrisError::PieceTooLarge { .. })),
+        "{error}"
+    );
+}
+
+#[test]
+fn the_held_piece_comes_out_when_the_queue_runs_dry() {
+    let mut tetris = start(&puzzle(&[".........."], &["T", "I"])).unwrap();
+    assert_eq!(name(&tetris), "T");
+    assert!(tetris.apply(Action::Hold));
+    assert_eq!(name(&tetris), "I");
+    assert!(tetris.queue().is_empty());
+
+    tetris.apply(Action::HardDrop);
+    while tetris.spawning() || tetris.pieces_locked() == 0 {
+        tetris.tick();
+    }
+    assert!(!tetris.game_over());
+    assert_eq!(name(&tetris), "T");
+    assert!(tetris.held().is_none());
+
+    tetris.apply(Action::HardDrop);
+    for _ in 0..10 {
+        tetris.tick();
+    }
+    assert!(tetris.game_over());
+    assert_eq!(tetris.pieces_locked(), 2);
+}
@@ -0,0 +1,53 @@
+//! The puzzles shipped with the game, checked here so the check runs without the game's
+//! graphics dependencies.
+
+mod common;
+
+use std::{fs, path::Path};
+
+use common::Counter;
+use tetris_core::puzzle::Puzzle;
+
+fn pack() -> Vec<Puzzle> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tetris-game/puzzles");
+    let mut paths: Vec<_> = fs::read_dir(&dir)
+        .expect("the game's puzzles are next to this crate")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|e| e == "toml"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no puzzles in {}", dir.display());
+    paths
+        .iter()
+        .map(|path| {
+            let text = fs::read_to_string(path).unwrap();
+            toml::from_str(&text).unwrap_or_else(|e| panic!("{}: {e}", path.display()))
+        })
+        .collect()
+}
+
+#[test]
+fn every_puzzle_is_solvable() {
+    for puzzle in pack() {
+        assert!(
+            puzzle.start(Counter::default()).is_ok(),
+            "{} does not load",
+            puzzle.name
+        );
+        assert!(
+            puzzle.solve::<Counter>().is_some(),
+            "{} has no solution",
+            puzzle.name
+        );
+    }
+}
+
+#[test]
+fn hold_puzzle_needs_hold() {
+    let mut puzzle = pack()
+        .into_iter()
+        .find(|p| p.name == "Save it for later")
+        .unwrap();
+    puzzle.hold = false;
+    assert!(puzzle.solve::<Counter>().is_none());
+}
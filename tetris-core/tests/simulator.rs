@@ -0,0 +1,109 @@
+mod common;
+
+use common::Counter;
+use tetris_core::{
+    action::Action,
+    error::TetrisError,
+    piece_set::PieceSet,
+    ruleset::Ruleset,
+    simulator::{GameResult, Simulator, Summary},
+    tetris::Tetris,
+};
+
+fn simulator() -> Simulator {
+    Simulator::new(Ruleset::guideline(), PieceSet::tetrominoes()).with_max_frames(3_000)
+}
+
+/// Drops every piece straight down, alternating sides so a line clears now and then.
+fn dropper() -> impl FnMut(&Tetris<Counter>) -> Option<Action> {
+    let mut n = 0;
+    move |tetris| {
+        if tetris.spawning() {
+            return None;
+        }
+        n += 1;
+        Some(match n % 3 {
+            0 => Action::HardDrop,
+            1 if n % 2 == 0 => Action::ShiftLeft,
+            1 => Action::ShiftRight,
+            _ => Action::RotateRight,
+        })
+    }
+}
+
+#[test]
+fn results_follow_the_seeds_whatever_the_threads() {
+    let seeds: Vec<i32> = (0..24).collect();
+    let one = simulator().with_threads(1).run(&seeds, dropper).unwrap();
+    let many = simulator().with_threads(6).run(&seeds, dropper).unwrap();
+    assert_eq!(one, many);
+    let played: Vec<i32> = one.iter().map(|r| r.seed).collect();
+    assert_eq!(played, seeds);
+    assert!(one.iter().all(|r| r.pieces > 0));
+}
+
+#[test]
+fn games_stop_at_a_top_out_or_the_frame_limit() {
+    let results = simulator()
+        .run(&[1, 2, 3], || |_: &Tetris<Counter>| Some(Action::HardDrop))
+        .unwrap();
+    assert!(results.iter().all(|r| r.topped_out && r.frames < 3_000));
+
+    let idle = simulator()
+        .with_max_frames(100)
+        .run(&[1, 2, 3], || |_: &Tetris<Counter>| None)
+        .unwrap();
+    assert!(idle.iter().all(|r| !r.topped_out && r.frames == 100));
+    assert_eq!(Summary::new(&idle).top_out_rate, 0.0);
+}
+
+#[test]
+fn boards_are_checked_before_playing() {
+    let error = simulator()
+        .with_size(3, 20)
+        .run(&[1], || |_: &Tetris<Counter>| None)
+        .unwrap_err();
+    assert!(matches!(error, TetrisError::PieceTooLarge { .. }));
+}
+
+#[test]
+#[should_panic(expected = "the player gave up")]
+fn panics_in_a_player_reach_the_caller() {
+    let _ = simulator().with_threads(2).run(&[1, 2], || {
+        |_: &Tetris<Counter>| -> Option<Action> { panic!("the player gave up") }
+    });
+}
+
+#[test]
+fn summaries_average_and_spread_the_scores() {
+    let result = |seed, score, lines, topped_out| GameResult {
+        seed,
+        score,
+        lines,
+        level: 1,
+        pieces: 10,
+        frames: 600,
+        topped_out,
+    };
+    let results = [
+        result(1, 400, 4, true),
+        result(2, 100, 1, false),
+        result(3, 900, 9, true),
+        result(4, 0, 0, true),
+        result(5, 1600, 16, false),
+    ];
+    let summary = Summary::new(&results);
+    assert_eq!(summary.games, 5);
+    assert_eq!(summary.mean_lines, 6.0);
+    assert_eq!(summary.mean_score, 600.0);
+    assert_eq!(summary.scores, [0, 100, 400, 900, 1600]);
+    assert_eq!(summary.top_out_rate, 0.6);
+
+    assert_eq!(Summary::CSV_HEADER.split(',').count(), 11);
+    assert_eq!(
+        summary.csv_row("nes, level 18"),
+        "\"nes, level 18\",5,6.00,600.00,0,100,400,900,1600,10.00,0.6000"
+    );
+    assert_eq!(results[0].csv_row(), "1,400,4,1,10,600,true");
+    assert_eq!(Summary::new(&[]), Summary::default());
+}
@@ -1,6 +1,7 @@
 use tetris_core::traits::HasSize;
 
-#[derive(Debug, Default, Clone, Copy)]
+/// A rectangle on screen, in pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct Area {
     position: (f32, f32),
     size: (f32, f32),
@@ -26,19 +27,23 @@ impl Area {
         self.position.0 + self.size.0
     }
     pub fn y_end(&self) -> f32 {
-        self.position.0 + self.size.1
+        self.position.1 + self.size.1
     }
     pub fn bounds(&self) -> (f32, f32, f32, f32) {
         (self.x(), self.y(), self.x_end(), self.y_end())
     }
+    /// The same rectangle moved by `(dx, dy)`.
+    pub fn offset(&self, (dx, dy): (f32, f32)) -> Self {
+        Self::new(self.x() + dx, self.y() + dy, self.size.0, self.size.1)
+    }
 }
 
 impl HasSize for Area {
     fn width(&self) -> i32 {
-        (self.size.0 - self.position.0) as i32
+        self.size.0 as i32
     }
 
     fn height(&self) -> i32 {
-        (self.size.1 - self.position.1) as i32
+        self.size.1 as i32
     }
 }
@@ -1,40 +1,271 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use raylib::prelude::*;
+use tetris_core::event::Event;
 
 pub const ROTATE_SOUND_BYTES: &[u8] = include_bytes!("../rotate.ogg");
 pub const WRONG_MOVE_SOUND_BYTES: &[u8] = include_bytes!("../wrong_move.ogg");
 
+/// File types looked up in a sound pack, in order of preference.
+const PACK_FILETYPES: &[&str] = &["ogg", "wav", "mp3", "flac"];
+
+/// How much higher each step of a combo plays the clear sound.
+const COMBO_PITCH_STEP: f32 = 0.06;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    Sfx,
+    Ui,
+    Music,
+}
+
+/// A sound shipped with the game: name, category, data, pitch and volume it is played at.
+type EmbeddedSound = (&'static str, Category, &'static [u8], f32, f32);
+
+/// Every sound the game plays, built from the two embedded samples until a sound pack replaces them.
+#[rustfmt::skip]
+const EMBEDDED_SOUNDS: &[EmbeddedSound] = &[
+    ("move",         Category::Sfx, ROTATE_SOUND_BYTES,     1.8,  0.4),
+    ("rotate",       Category::Sfx, ROTATE_SOUND_BYTES,     1.0,  1.0),
+    ("wrong",        Category::Sfx, WRONG_MOVE_SOUND_BYTES, 1.0,  1.0),
+    ("lock",         Category::Sfx, WRONG_MOVE_SOUND_BYTES, 0.6,  0.6),
+    ("hard_drop",    Category::Sfx, WRONG_MOVE_SOUND_BYTES, 0.45, 1.0),
+    ("hold",         Category::Sfx, ROTATE_SOUND_BYTES,     0.7,  0.8),
+    ("single",       Category::Sfx, ROTATE_SOUND_BYTES,     1.2,  1.0),
+    ("double",       Category::Sfx, ROTATE_SOUND_BYTES,     1.35, 1.0),
+    ("triple",       Category::Sfx, ROTATE_SOUND_BYTES,     1.5,  1.0),
+    ("tetris",       Category::Sfx, ROTATE_SOUND_BYTES,     1.7,  1.0),
+    ("t_spin",       Category::Sfx, ROTATE_SOUND_BYTES,     0.85, 1.0),
+    ("level_up",     Category::Sfx, ROTATE_SOUND_BYTES,     2.0,  1.0),
+    ("game_over",    Category::Sfx, WRONG_MOVE_SOUND_BYTES, 0.35, 1.0),
+    ("menu_move",    Category::Ui,  ROTATE_SOUND_BYTES,     1.5,  0.5),
+    ("menu_confirm", Category::Ui,  ROTATE_SOUND_BYTES,     1.2,  0.8),
+];
+
+#[derive(Debug)]
+struct SoundEntry<'aud> {
+    sound: Sound<'aud>,
+    category: Category,
+    pitch: f32,
+    volume: f32,
+}
+
+#[derive(Debug)]
+struct Track<'aud> {
+    music: Music<'aud>,
+    loop_start: f32,
+    loop_end: Option<f32>,
+    paused: bool,
+}
+
+/// Named sound registry and the only way the game plays audio.
 #[derive(Debug)]
 pub struct AudioBox<'aud> {
-    audio_subsystem: RaylibAudio,
-    sounds: HashMap<String, (Wave<'aud>, Sound<'aud>)>,
+    audio_subsystem: &'aud RaylibAudio,
+    sounds: HashMap<String, SoundEntry<'aud>>,
+    volumes: HashMap<Category, f32>,
+    music: Option<Track<'aud>>,
+    pack: Option<PathBuf>,
 }
 
 impl<'aud> AudioBox<'aud> {
-    pub fn new() -> Self {
-        let audio_subsystem = RaylibAudio::init_audio_device();
-        Self {
-            audio_subsystem: audio_subsystem.unwrap(),
+    /// Creates the registry with every embedded sound, letting files in `pack` override them.
+    pub fn new(audio_subsystem: &'aud RaylibAudio, pack: Option<&Path>) -> Self {
+        let mut audio = Self {
+            audio_subsystem,
             sounds: HashMap::new(),
+            volumes: HashMap::new(),
+            music: None,
+            pack: pack.map(Path::to_path_buf),
+        };
+        for &(name, category, bytes, pitch, volume) in EMBEDDED_SOUNDS {
+            audio.load_sound_from_bytes(name, category, bytes, ".ogg", pitch, volume);
         }
+        audio.load_pack_music();
+        audio
+    }
+
+    fn pack_file(&self, name: &str) -> Option<PathBuf> {
+        let pack = self.pack.as_ref()?;
+        PACK_FILETYPES
+            .iter()
+            .map(|ext| pack.join(format!("{name}.{ext}")))
+            .find(|path| path.is_file())
     }
 
-    pub fn load_sound_from_bytes(&'aud mut self, name: &str, bytes: &[u8], filetype: &str) {
-        let wav = self
-            .audio_subsystem
-            .new_wave_from_memory(filetype, bytes)
-            .unwrap();
-        let sound = self.audio_subsystem.new_sound_from_wave(&wav).unwrap();
-        self.sounds.insert(name.to_owned(), (wav, sound));
+    /// Registers a sound under `name`, unless the sound pack has a file with that name.
+    pub fn load_sound_from_bytes(
+        &mut self,
+        name: &str,
+        category: Category,
+        bytes: &[u8],
+        filetype: &str,
+        pitch: f32,
+        volume: f32,
+    ) {
+        let loaded = match self.pack_file(name) {
+            Some(path) => self
+                .audio_subsystem
+                .new_sound(&path.to_string_lossy())
+                .map(|sound| (sound, 1.0, 1.0)),
+            None => self
+                .audio_subsystem
+                .new_wave_from_memory(filetype, bytes)
+                .and_then(|wave| self.audio_subsystem.new_sound_from_wave(&wave))
+                .map(|sound| (sound, pitch, volume)),
+        };
+
+        match loaded {
+            Ok((sound, pitch, volume)) => {
+                sound.set_volume(volume * self.volume(category));
+                self.sounds.insert(
+                    name.to_owned(),
+                    SoundEntry {
+                        sound,
+                        category,
+                        pitch,
+                        volume,
+                    },
+                );
+            }
+            Err(e) => eprintln!("Could not load sound {name}: {e}"),
+        }
+    }
+
+    /// Loads `music.*` from the sound pack, with loop points read from `music.loop` as
+    /// `start end` in seconds. Without an end point the whole track loops.
+    fn load_pack_music(&mut self) {
+        let Some(path) = self.pack_file("music") else {
+            return;
+        };
+        let music = match self.audio_subsystem.new_music(&path.to_string_lossy()) {
+            Ok(music) => music,
+            Err(e) => {
+                eprintln!("Could not load music: {e}");
+                return;
+            }
+        };
+
+        let points: Vec<f32> = self
+            .pack
+            .as_ref()
+            .and_then(|pack| fs::read_to_string(pack.join("music.loop")).ok())
+            .map(|text| {
+                text.split_whitespace()
+                    .filter_map(|v| v.parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        self.music = Some(Track {
+            music,
+            loop_start: points.first().copied().unwrap_or(0.0),
+            loop_end: points.get(1).copied(),
+            paused: false,
+        });
+        self.apply_volume(Category::Music);
+    }
+
+    pub fn set_volume(&mut self, category: Category, volume: f32) {
+        if self.volume(category) != volume {
+            self.volumes.insert(category, volume);
+            self.apply_volume(category);
+        }
+    }
+
+    fn volume(&self, category: Category) -> f32 {
+        self.volumes.get(&category).copied().unwrap_or(1.0)
+    }
+
+    fn apply_volume(&self, category: Category) {
+        let volume = self.volume(category);
+        for entry in self.sounds.values().filter(|e| e.category == category) {
+            entry.sound.set_volume(entry.volume * volume);
+        }
+        if let (Category::Music, Some(track)) = (category, &self.music) {
+            track.music.set_volume(volume);
+        }
     }
 
     pub fn play_sound(&self, name: &str) -> bool {
-        if let Some((_, sound)) = self.sounds.get(name) {
-            sound.play();
+        self.play_sound_pitched(name, 1.0)
+    }
+
+    /// Plays the sound with its pitch multiplied by `pitch`.
+    pub fn play_sound_pitched(&self, name: &str, pitch: f32) -> bool {
+        if let Some(entry) = self.sounds.get(name) {
+            entry.sound.set_pitch(entry.pitch * pitch);
+            entry.sound.play();
             return true;
         }
 
         false
     }
+
+    pub fn play_event(&self, event: &Event) {
+        match event {
+            Event::Locked(_) => {
+                self.play_sound("lock");
+            }
+            Event::HardDropped { .. } => {
+                self.play_sound("hard_drop");
+            }
+            Event::LinesCleared { rows, combo } => {
+                let name = match rows.len() {
+                    1 => "single",
+                    2 => "double",
+                    3 => "triple",
+                    _ => "tetris",
+                };
+                self.play_sound_pitched(name, 1.0 + *combo as f32 * COMBO_PITCH_STEP);
+            }
+            Event::TSpin { .. } => {
+                self.play_sound("t_spin");
+            }
+            Event::LevelUp(_) => {
+                self.play_sound("level_up");
+            }
+            Event::GameOver => {
+                self.play_sound("game_over");
+            }
+        }
+    }
+
+    pub fn play_music(&self) {
+        if let Some(track) = &self.music {
+            track.music.play_stream();
+        }
+    }
+
+    pub fn pause_music(&mut self, paused: bool) {
+        if let Some(track) = self.music.as_mut().filter(|t| t.paused != paused) {
+            if paused {
+                track.music.pause_stream();
+            } else {
+                track.music.resume_stream();
+            }
+            track.paused = paused;
+        }
+    }
+
+    /// Feeds the music stream, must be called every frame while music plays.
+    pub fn update(&self) {
+        let Some(track) = &self.music else {
+            return;
+        };
+        track.music.update_stream();
+        if let Some(end) = track.loop_end {
+            if track.music.get_time_played() >= end {
+                track.music.seek_stream(track.loop_start);
+            }
+        } else if track.loop_start > 0.0
+            && track.music.get_time_played() >= track.music.get_time_length() - 0.05
+        {
+            track.music.seek_stream(track.loop_start);
+        }
+    }
 }
@@ -1,15 +1,60 @@
+use std::{fs, path::PathBuf};
+
 use clap::Parser;
+use tetris_core::prelude::Ruleset;
 
 /// Customize the gameplay
 #[derive(Debug, Parser)]
 pub struct Config {
-    /// Number of columns
-    #[arg(short = 'W', long, default_value_t = tetris_core::board::WIDTH)]
-    pub width: u32,
-    /// Number of rows
-    #[arg(short = 'H', long, default_value_t = tetris_core::board::HEIGHT)]
-    pub height: u32,
+    /// Number of columns, overrides the options menu
+    #[arg(short = 'W', long)]
+    pub width: Option<u32>,
+    /// Number of rows, overrides the options menu
+    #[arg(short = 'H', long)]
+    pub height: Option<u32>,
     /// Randomizer seed
     #[arg(short, long)]
     pub seed: Option<i32>,
+    /// Directory of sounds and music overriding the built-in ones
+    #[arg(long, value_name = "DIR")]
+    pub sound_pack: Option<PathBuf>,
+    /// Broadcast the game to spectators on this address (e.g. 127.0.0.1:7878)
+    #[arg(long, value_name = "ADDR")]
+    pub serve: Option<String>,
+    /// Watch a game broadcast on this address instead of playing
+    #[arg(long, value_name = "ADDR", conflicts_with = "serve")]
+    pub spectate: Option<String>,
+    /// Rules to play by: guideline, nes, tgm, casual or a TOML/RON ruleset file
+    #[arg(short, long, value_name = "PRESET|FILE")]
+    pub ruleset: Option<String>,
+    /// Save the inputs of every finished game to this file, except in practice and puzzles
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+    /// Play back a recorded game instead of playing
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["record", "spectate"])]
+    pub replay: Option<PathBuf>,
+}
+
+impl Config {
+    /// The preset or file named by `--ruleset`, the guideline rules without one. Fields missing
+    /// from a file keep their guideline values.
+    pub fn ruleset(&self) -> Result<Ruleset, String> {
+        let Some(name) = &self.ruleset else {
+            return Ok(Ruleset::default());
+        };
+        if let Some(preset) = Ruleset::preset(name) {
+            return Ok(preset);
+        }
+
+        let path = PathBuf::from(name);
+        let text = fs::read_to_string(&path).map_err(|e| format!("{name}: {e}"))?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("ron") => ron::from_str(&text).map_err(|e| format!("{name}: {e}")),
+            Some("toml") => toml::from_str(&text).map_err(|e| format!("{name}: {e}")),
+            _ => Err(format!(
+                "{name} is neither a preset ({}) nor a .toml or .ron file",
+                Ruleset::PRESETS.join(", ")
+            )),
+        }
+    }
 }
@@ -0,0 +1,43 @@
+use tetris_bot::Bot;
+use tetris_core::{action::Action, tetris::Tetris, traits::Randomizer};
+
+/// A bot held to a human pace, sending at most one input every `delay` frames.
+#[derive(Debug, Clone)]
+pub struct Cpu {
+    bot: Bot,
+    delay: u32,
+    wait: u32,
+}
+
+impl Cpu {
+    pub fn new(delay: u32) -> Self {
+        Self {
+            bot: Bot::default(),
+            delay,
+            wait: 0,
+        }
+    }
+
+    /// The inputs for this frame.
+    pub fn actions<R: Randomizer>(&mut self, tetris: &Tetris<R>) -> Vec<Action> {
+        if self.wait > 0 {
+            self.wait -= 1;
+            return vec![];
+        }
+        let action = self.bot.next_action(tetris);
+        if action.is_some() {
+            self.wait = self.delay;
+        }
+        action.into_iter().collect()
+    }
+}
+
+/// Garbage rows sent to the other side of a versus game for clearing `lines` lines at once.
+pub fn attack(lines: usize) -> u32 {
+    match lines {
+        0 | 1 => 0,
+        2 => 1,
+        3 => 2,
+        _ => 4,
+    }
+}
@@ -0,0 +1,319 @@
+use rand::Rng;
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+use tetris_core::event::Event;
+
+use crate::area::Area;
+
+const LOCK_FLASH_FRAMES: u32 = 8;
+const TRAIL_FRAMES: u32 = 10;
+const SHAKE_DECAY: f32 = 0.85;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClearAnimation {
+    /// Cleared rows flash and fade out.
+    Flash,
+    /// Cleared rows flash while the stack above falls into place.
+    Collapse,
+    Off,
+}
+
+impl ClearAnimation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClearAnimation::Flash => "flash",
+            ClearAnimation::Collapse => "collapse",
+            ClearAnimation::Off => "off",
+        }
+    }
+
+    pub fn cycle(&self, delta: i32) -> Self {
+        const ALL: [ClearAnimation; 3] = [
+            ClearAnimation::Flash,
+            ClearAnimation::Collapse,
+            ClearAnimation::Off,
+        ];
+        let current = ALL.iter().position(|a| a == self).unwrap_or(0) as i32;
+        ALL[(current + delta).rem_euclid(ALL.len() as i32) as usize]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EffectSettings {
+    /// How long the line clear animation lasts.
+    pub line_clear_frames: u32,
+    pub clear_animation: ClearAnimation,
+    pub lock_flash: bool,
+    pub hard_drop_trail: bool,
+    pub particles: bool,
+    pub screen_shake: bool,
+}
+
+impl Default for EffectSettings {
+    fn default() -> Self {
+        Self {
+            line_clear_frames: 20,
+            clear_animation: ClearAnimation::Collapse,
+            lock_flash: true,
+            hard_drop_trail: true,
+            particles: true,
+            screen_shake: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Timed<T> {
+    value: T,
+    frame: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Trail {
+    cells: Vec<(i32, i32)>,
+    distance: i32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Particle {
+    position: (f32, f32),
+    velocity: (f32, f32),
+    color: Color,
+    life: u32,
+    frame: u32,
+}
+
+/// Short lived decorations drawn on top of the playfield, positioned in cell units.
+#[derive(Debug, Clone, Default)]
+pub struct Effects {
+    settings: EffectSettings,
+    clears: Vec<Timed<Vec<i32>>>,
+    locks: Vec<Timed<Vec<(i32, i32)>>>,
+    trails: Vec<Timed<Trail>>,
+    particles: Vec<Particle>,
+    shake: f32,
+}
+
+impl Effects {
+    pub fn new(settings: &EffectSettings) -> Self {
+        Self {
+            settings: settings.clone(),
+            ..Default::default()
+        }
+    }
+
+    pub fn handle(&mut self, event: &Event, board_width: i32) {
+        let mut rng = rand::thread_rng();
+        match event {
+            Event::Locked(cells) => {
+                if self.settings.lock_flash {
+                    self.locks.push(Timed {
+                        value: cells.clone(),
+                        frame: 0,
+                    });
+                }
+            }
+            Event::HardDropped { cells, distance } => {
+                if self.settings.hard_drop_trail && *distance > 0 {
+                    self.trails.push(Timed {
+                        value: Trail {
+                            cells: cells.clone(),
+                            distance: *distance,
+                        },
+                        frame: 0,
+                    });
+                }
+                if self.settings.screen_shake {
+                    self.shake += 0.05 + 0.02 * (*distance).min(10) as f32;
+                }
+                if self.settings.particles {
+                    let bottom = cells.iter().map(|&(_, y)| y).max().unwrap_or(0);
+                    for &(x, _) in cells.iter().filter(|&&(_, y)| y == bottom) {
+                        for _ in 0..2 {
+                            self.particles.push(Particle {
+                                position: (x as f32 + rng.gen_range(0.0..1.0), bottom as f32 + 1.0),
+                                velocity: (rng.gen_range(-0.08..0.08), rng.gen_range(-0.12..-0.02)),
+                                color: Color::LIGHTGRAY,
+                                life: rng.gen_range(10..20),
+                                frame: 0,
+                            });
+                        }
+                    }
+                }
+            }
+            Event::LinesCleared { rows, .. } => {
+                if self.settings.clear_animation != ClearAnimation::Off {
+                    self.clears.push(Timed {
+                        value: rows.clone(),
+                        frame: 0,
+                    });
+                }
+                if self.settings.screen_shake {
+                    self.shake += 0.1 * rows.len() as f32;
+                }
+                if self.settings.particles {
+                    for &y in rows {
+                        for x in 0..board_width {
+                            for _ in 0..3 {
+                                self.particles.push(Particle {
+                                    position: (x as f32 + 0.5, y as f32 + 0.5),
+                                    velocity: (rng.gen_range(-0.2..0.2), rng.gen_range(-0.4..0.0)),
+                                    color: if rng.gen_bool(0.5) {
+                                        Color::WHITE
+                                    } else {
+                                        Color::GOLD
+                                    },
+                                    life: rng.gen_range(30..50),
+                                    frame: 0,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            Event::TSpin { .. } | Event::LevelUp(_) | Event::GameOver => {}
+        }
+    }
+
+    /// Advances every effect by one frame and drops the finished ones.
+    pub fn update(&mut self) {
+        let clear_frames = self.settings.line_clear_frames;
+        self.clears.retain_mut(|c| {
+            c.frame += 1;
+            c.frame < clear_frames
+        });
+        self.locks.retain_mut(|l| {
+            l.frame += 1;
+            l.frame < LOCK_FLASH_FRAMES
+        });
+        self.trails.retain_mut(|t| {
+            t.frame += 1;
+            t.frame < TRAIL_FRAMES
+        });
+        self.particles.retain_mut(|p| {
+            p.frame += 1;
+            p.position.0 += p.velocity.0;
+            p.position.1 += p.velocity.1;
+            p.velocity.1 += 0.02;
+            p.frame < p.life
+        });
+        self.shake *= SHAKE_DECAY;
+        if self.shake < 0.01 {
+            self.shake = 0.0;
+        }
+    }
+
+    /// Random offset in cells to apply to the whole playfield this frame.
+    pub fn shake_offset(&self) -> (f32, f32) {
+        if self.shake == 0.0 {
+            return (0.0, 0.0);
+        }
+        let mut rng = rand::thread_rng();
+        (
+            rng.gen_range(-self.shake..self.shake),
+            rng.gen_range(-self.shake..self.shake),
+        )
+    }
+
+    /// How many cells above its real position row `y` should be drawn while the stack collapses.
+    pub fn row_offset(&self, y: i32, board_height: i32) -> f32 {
+        if self.settings.clear_animation != ClearAnimation::Collapse {
+            return 0.0;
+        }
+        self.clears
+            .iter()
+            .map(|clear| {
+                // Count the surviving rows from the bottom up to find where `y` came from.
+                let mut origin = board_height;
+                for _ in y..board_height {
+                    origin -= 1;
+                    while clear.value.contains(&origin) {
+                        origin -= 1;
+                    }
+                }
+                let progress = clear.frame as f32 / self.settings.line_clear_frames.max(1) as f32;
+                (y - origin) as f32 * (1.0 - progress)
+            })
+            .sum()
+    }
+
+    pub fn draw(
+        &self,
+        draw: &mut RaylibDrawHandle,
+        playfield: &Area,
+        cell_size: f32,
+        board_width: i32,
+        alpha: f32,
+    ) {
+        let to_screen =
+            |x: f32, y: f32| (playfield.x() + x * cell_size, playfield.y() + y * cell_size);
+        let fade = |frame: u32, total: u32, max: f32| {
+            ((1.0 - frame as f32 / total.max(1) as f32) * max * 255.0) as u8
+        };
+
+        for trail in &self.trails {
+            let Trail { cells, distance } = &trail.value;
+            let alpha = fade(trail.frame, TRAIL_FRAMES, 0.35);
+            for &(x, y) in cells {
+                if cells.contains(&(x, y - 1)) {
+                    continue;
+                }
+                let (sx, sy) = to_screen(x as f32, (y - distance) as f32);
+                draw.draw_rectangle(
+                    sx as i32,
+                    sy as i32,
+                    cell_size as i32,
+                    (*distance as f32 * cell_size) as i32,
+                    Color::new(255, 255, 255, alpha),
+                );
+            }
+        }
+
+        for lock in &self.locks {
+            let alpha = fade(lock.frame, LOCK_FLASH_FRAMES, 0.8);
+            for &(x, y) in &lock.value {
+                let (sx, sy) = to_screen(x as f32, y as f32);
+                draw.draw_rectangle(
+                    sx as i32,
+                    sy as i32,
+                    cell_size.ceil() as i32,
+                    cell_size.ceil() as i32,
+                    Color::new(255, 255, 255, alpha),
+                );
+            }
+        }
+
+        for clear in &self.clears {
+            let alpha = fade(clear.frame, self.settings.line_clear_frames, 0.9);
+            for &y in &clear.value {
+                let (sx, sy) = to_screen(0.0, y as f32);
+                draw.draw_rectangle(
+                    sx as i32,
+                    sy as i32,
+                    (board_width as f32 * cell_size) as i32,
+                    cell_size.ceil() as i32,
+                    Color::new(255, 255, 255, alpha),
+                );
+            }
+        }
+
+        for p in &self.particles {
+            // Carry particles on along their velocity between two simulation steps.
+            let (sx, sy) = to_screen(
+                p.position.0 + p.velocity.0 * alpha,
+                p.position.1 + p.velocity.1 * alpha,
+            );
+            let size = (cell_size / 8.0).max(2.0);
+            let alpha = fade(p.frame, p.life, 1.0);
+            draw.draw_rectangle(
+                sx as i32,
+                sy as i32,
+                size as i32,
+                size as i32,
+                Color::new(p.color.r, p.color.g, p.color.b, alpha),
+            );
+        }
+    }
+}
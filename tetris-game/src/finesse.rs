@@ -0,0 +1,351 @@
+use tetris_bot::Bot;
+use tetris_core::{
+    action::Action,
+    brick::Brick,
+    placement::{self, Placement},
+    prelude::{Event, Tetris},
+    traits::Randomizer,
+};
+
+use crate::input::GameAction;
+
+/// How the last piece was placed compared with the fewest presses that put it there.
+#[derive(Debug, Clone)]
+pub struct Verdict {
+    pub presses: u32,
+    pub optimal: Vec<Action>,
+}
+
+impl Verdict {
+    pub fn faults(&self) -> u32 {
+        self.presses.saturating_sub(press_count(&self.optimal))
+    }
+}
+
+/// Counts the keys pressed for every piece and compares them with the shortest way to the
+/// same spot, from where the piece was when it came into play.
+#[derive(Debug, Clone)]
+pub struct Finesse {
+    /// Where the piece in play can go, unset until it is in play.
+    placements: Option<Vec<Placement>>,
+    presses: u32,
+    /// Picks a spot for every piece in the trainer, unset when only counting faults.
+    bot: Option<Bot>,
+    /// The fewest inputs from the spawn position to the spot picked for the piece in play,
+    /// a hold first if the spot is for the piece that comes out of it.
+    pub suggestion: Option<Vec<Action>>,
+    pub pieces: u32,
+    /// Presses beyond the optimal ones, over the whole game.
+    pub faults: u32,
+    /// Pieces placed with at least one fault.
+    pub faulty_pieces: u32,
+    pub last: Option<Verdict>,
+}
+
+impl Default for Finesse {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Finesse {
+    pub fn new() -> Self {
+        Self {
+            placements: None,
+            presses: 0,
+            bot: None,
+            suggestion: None,
+            pieces: 0,
+            faults: 0,
+            faulty_pieces: 0,
+            last: None,
+        }
+    }
+
+    /// Counts faults and also suggests where each piece could go, with the inputs to get
+    /// it there, before it is moved.
+    pub fn trainer() -> Self {
+        Self {
+            bot: Some(Bot::default()),
+            ..Self::new()
+        }
+    }
+
+    /// Call every step before the inputs are applied, with the keys newly pressed in it.
+    pub fn track<R: Randomizer>(&mut self, tetris: &Tetris<R>, presses: &[GameAction]) {
+        if self.placements.is_none() && !tetris.spawning() {
+            // Judged from the spawn position, before gravity or a rotation pressed while
+            // waiting for the piece moved it. Those presses count towards it.
+            let kind = tetris.player().brick().kind();
+            let brick = tetris.pieces().piece(kind).brick;
+            let placements = from_spawn(tetris, brick);
+            self.suggestion = self.suggest(tetris, &placements);
+            self.placements = Some(placements);
+        }
+        self.presses += presses
+            .iter()
+            .filter(|a| !matches!(a, GameAction::HardDrop | GameAction::Hold))
+            .filter(|a| a.action().is_some())
+            .count() as u32;
+    }
+
+    /// The inputs to the spot the bot picks for the piece in play, `placements` being where
+    /// that piece can go from its spawn position.
+    fn suggest<R: Randomizer>(
+        &self,
+        tetris: &Tetris<R>,
+        placements: &[Placement],
+    ) -> Option<Vec<Action>> {
+        let plan = self.bot.as_ref()?.plan(tetris)?;
+        let cells = plan.placement.cells();
+        if !plan.hold {
+            let best = placements.iter().find(|p| p.cells() == cells)?;
+            return Some(best.inputs.clone());
+        }
+        let brick = tetris.held().or_else(|| tetris.queue().first().copied())?;
+        let best = from_spawn(tetris, brick)
+            .into_iter()
+            .find(|p| p.cells() == cells)?;
+        Some([Action::Hold].into_iter().chain(best.inputs).collect())
+    }
+
+    /// A successful hold starts over with the piece that comes out.
+    pub fn held(&mut self) {
+        self.placements = None;
+        self.suggestion = None;
+        self.presses = 0;
+    }
+
+    pub fn handle(&mut self, event: &Event) {
+        let Event::Locked(cells) = event else {
+            return;
+        };
+        let presses = std::mem::take(&mut self.presses);
+        self.suggestion = None;
+        let Some(placements) = self.placements.take() else {
+            return;
+        };
+        let mut cells = cells.clone();
+        cells.sort_unstable();
+        // Pieces that got somewhere only gravity could take them are not judged.
+        let Some(best) = placements.into_iter().find(|p| p.cells() == cells) else {
+            return;
+        };
+
+        let verdict = Verdict {
+            presses,
+            optimal: best.inputs,
+        };
+        self.pieces += 1;
+        if verdict.faults() > 0 {
+            self.faults += verdict.faults();
+            self.faulty_pieces += 1;
+        }
+        self.last = Some(verdict);
+    }
+
+    /// One line for the results screen.
+    pub fn summary(&self) -> String {
+        let clean = self.pieces - self.faulty_pieces;
+        let percent = (clean * 100).checked_div(self.pieces).unwrap_or(100);
+        format!(
+            "Finesse: {} faults, {clean}/{} pieces clean ({percent}%)",
+            self.faults, self.pieces
+        )
+    }
+}
+
+/// Where a piece of `brick`'s kind can go in `tetris` from its spawn position.
+fn from_spawn<R: Randomizer>(tetris: &Tetris<R>, brick: Brick) -> Vec<Placement> {
+    let start = tetris.ruleset().spawn.player_for(tetris.board(), brick);
+    placement::placements(tetris.board(), start, tetris.ruleset(), tetris.pieces())
+}
+
+/// Presses needed for `inputs`: a held soft drop is a single press, the hard drop is free.
+pub fn press_count(inputs: &[Action]) -> u32 {
+    let mut count = 0;
+    let mut previous = None;
+    for &input in inputs {
+        let held_down = input == Action::SoftDrop && previous == Some(Action::SoftDrop);
+        if input != Action::HardDrop && !held_down {
+            count += 1;
+        }
+        previous = Some(input);
+    }
+    count
+}
+
+pub fn input_label(action: Action) -> &'static str {
+    match action {
+        Action::MoveLeft => "L",
+        Action::MoveRight => "R",
+        Action::ShiftLeft => "DAS L",
+        Action::ShiftRight => "DAS R",
+        Action::SoftDrop => "Down",
+        Action::HardDrop => "Drop",
+        Action::RotateLeft => "CCW",
+        Action::RotateRight => "CW",
+        Action::Hold => "Hold",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetris_core::{piece_set::PieceSet, ruleset::Ruleset};
+
+    use super::*;
+    use crate::DummyRng;
+
+    /// Steps `tetris` once with `presses`, the way the game loop does.
+    fn step(tetris: &mut Tetris<DummyRng>, finesse: &mut Finesse, presses: &[GameAction]) {
+        finesse.track(tetris, presses);
+        for action in presses.iter().filter_map(GameAction::action) {
+            if tetris.apply(action) && action == Action::Hold {
+                finesse.held();
+            }
+        }
+        tetris.tick();
+        for event in tetris.drain_events() {
+            finesse.handle(&event);
+        }
+    }
+
+    #[test]
+    fn rotations_pressed_before_the_piece_spawns_count() {
+        let ruleset = Ruleset {
+            are: 10,
+            ..Ruleset::guideline()
+        };
+        let pieces = PieceSet::tetrominoes();
+        let mut tetris = Tetris::with_ruleset(10, 20, ruleset, pieces, DummyRng::new()).unwrap();
+        let mut finesse = Finesse::new();
+        while tetris.spawning() {
+            step(&mut tetris, &mut finesse, &[]);
+        }
+        step(&mut tetris, &mut finesse, &[GameAction::HardDrop]);
+        assert!(tetris.spawning());
+
+        // Turned one way, back and the other way while waiting: only the last one counts
+        // for the rotation, all three for the presses.
+        for rotate in [
+            GameAction::RotateRight,
+            GameAction::RotateLeft,
+            GameAction::RotateLeft,
+        ] {
+            step(&mut tetris, &mut finesse, &[rotate]);
+        }
+        while tetris.spawning() {
+            step(&mut tetris, &mut finesse, &[]);
+        }
+        step(&mut tetris, &mut finesse, &[GameAction::HardDrop]);
+
+        let last = finesse.last.as_ref().unwrap();
+        assert_eq!(last.presses, 3);
+        assert_eq!(last.optimal, [Action::RotateLeft, Action::HardDrop]);
+        assert_eq!(last.faults(), 2);
+        assert_eq!(finesse.pieces, 2);
+    }
+
+    fn press(action: Action) -> GameAction {
+        match action {
+            Action::MoveLeft => GameAction::MoveLeft,
+            Action::MoveRight => GameAction::MoveRight,
+            Action::ShiftLeft => GameAction::ShiftLeft,
+            Action::ShiftRight => GameAction::ShiftRight,
+            Action::SoftDrop => GameAction::SoftDrop,
+            Action::HardDrop => GameAction::HardDrop,
+            Action::RotateLeft => GameAction::RotateLeft,
+            Action::RotateRight => GameAction::RotateRight,
+            Action::Hold => GameAction::Hold,
+        }
+    }
+
+    /// Plays the piece in play with `inputs`, one a step and a held soft drop as one press.
+    fn play(tetris: &mut Tetris<DummyRng>, finesse: &mut Finesse, inputs: &[Action]) {
+        let mut previous = None;
+        for &input in inputs {
+            let held_down = input == Action::SoftDrop && previous == Some(input);
+            if held_down {
+                finesse.track(tetris, &[]);
+                tetris.apply(input);
+                tetris.tick();
+                for event in tetris.drain_events() {
+                    finesse.handle(&event);
+                }
+            } else {
+                step(tetris, finesse, &[press(input)]);
+            }
+            previous = Some(input);
+        }
+    }
+
+    /// Waits for the next piece and returns what the trainer suggests for it.
+    fn next_suggestion(tetris: &mut Tetris<DummyRng>, finesse: &mut Finesse) -> Vec<Action> {
+        while tetris.spawning() {
+            step(tetris, finesse, &[]);
+        }
+        // The trainer picks a spot once the piece is in play, before anything is pressed.
+        step(tetris, finesse, &[]);
+        finesse
+            .suggestion
+            .clone()
+            .expect("the trainer suggests a spot")
+    }
+
+    #[test]
+    fn following_the_suggestions_is_fault_free() {
+        let pieces = PieceSet::tetrominoes();
+        let ruleset = Ruleset::guideline();
+        let mut tetris = Tetris::with_ruleset(10, 20, ruleset, pieces, DummyRng::new()).unwrap();
+        let mut finesse = Finesse::trainer();
+        let mut moved = 0;
+        for placed in 1..=14 {
+            let suggestion = next_suggestion(&mut tetris, &mut finesse);
+            assert_eq!(suggestion.last(), Some(&Action::HardDrop));
+            if press_count(&suggestion) > 0 {
+                moved += 1;
+            }
+            play(&mut tetris, &mut finesse, &suggestion);
+            assert_eq!(finesse.pieces, placed);
+            assert_eq!(finesse.last.as_ref().unwrap().faults(), 0, "{suggestion:?}");
+        }
+        assert!(!tetris.game_over());
+        assert_eq!(finesse.faults, 0);
+        // The pieces do not all just drop where they spawn.
+        assert!(moved >= 7, "{moved} pieces moved");
+    }
+
+    #[test]
+    fn presses_beyond_the_suggestion_are_faults() {
+        let pieces = PieceSet::tetrominoes();
+        let ruleset = Ruleset::guideline();
+        let mut tetris = Tetris::with_ruleset(10, 20, ruleset, pieces, DummyRng::new()).unwrap();
+        let mut finesse = Finesse::trainer();
+        for extra in 0..3 {
+            let suggestion = next_suggestion(&mut tetris, &mut finesse);
+            let (drop, moves) = suggestion.split_last().unwrap();
+            // Steps left and back again, two wasted presses each, then the suggestion.
+            let mut inputs = vec![Action::MoveLeft; extra];
+            inputs.extend(vec![Action::MoveRight; extra]);
+            inputs.extend(moves.iter().filter(|&&a| a != Action::Hold));
+            inputs.push(*drop);
+            if moves.first() == Some(&Action::Hold) {
+                inputs.insert(0, Action::Hold);
+            }
+            play(&mut tetris, &mut finesse, &inputs);
+            assert_eq!(finesse.last.as_ref().unwrap().faults(), 2 * extra as u32);
+        }
+        assert_eq!(finesse.faults, 2 + 4);
+        assert_eq!(finesse.faulty_pieces, 2);
+    }
+
+    #[test]
+    fn only_the_trainer_suggests() {
+        let pieces = PieceSet::tetrominoes();
+        let ruleset = Ruleset::guideline();
+        let mut tetris = Tetris::with_ruleset(10, 20, ruleset, pieces, DummyRng::new()).unwrap();
+        let mut finesse = Finesse::new();
+        step(&mut tetris, &mut finesse, &[]);
+        assert!(finesse.suggestion.is_none());
+    }
+}
@@ -0,0 +1,246 @@
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+use tetris_core::{event::Event, tetris::Tetris, traits::Randomizer};
+
+use crate::area::Area;
+
+const CALLOUT_FRAMES: u32 = 60;
+
+/// A number shown in the column left of the playfield.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Readout {
+    Score,
+    Level,
+    Lines,
+    Time,
+    /// Pieces per second.
+    Pps,
+}
+
+impl Readout {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Readout::Score => "Score",
+            Readout::Level => "Level",
+            Readout::Lines => "Lines",
+            Readout::Time => "Time",
+            Readout::Pps => "PPS",
+        }
+    }
+
+    pub fn value<R: Randomizer>(&self, tetris: &Tetris<R>) -> String {
+        match self {
+            Readout::Score => format!("{:0>5}", tetris.score()),
+            Readout::Level => tetris.level().to_string(),
+            Readout::Lines => tetris.lines().to_string(),
+            Readout::Time => {
+                let seconds = tetris.stats().seconds();
+                format!("{}:{:04.1}", (seconds / 60.0) as u32, seconds % 60.0)
+            }
+            Readout::Pps => format!("{:.2}", tetris.stats().pps()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HudSettings {
+    /// Readouts from the top down, under the hold box.
+    pub readouts: Vec<Readout>,
+    /// Names clears, combos and back-to-backs over the playfield as they happen.
+    pub callouts: bool,
+}
+
+impl Default for HudSettings {
+    fn default() -> Self {
+        Self {
+            readouts: vec![
+                Readout::Score,
+                Readout::Level,
+                Readout::Lines,
+                Readout::Time,
+                Readout::Pps,
+            ],
+            callouts: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Callout {
+    text: String,
+    color: Color,
+}
+
+/// The readouts of one board and the callouts of its latest clear.
+#[derive(Debug, Clone, Default)]
+pub struct Hud {
+    settings: HudSettings,
+    /// Lines of the latest callout from the top down, all fading together.
+    callouts: Vec<Callout>,
+    frame: u32,
+    /// The clear being called out was a T-spin, so it is not named again as a plain one.
+    t_spin: bool,
+}
+
+impl Hud {
+    pub fn new(settings: &HudSettings) -> Self {
+        Self {
+            settings: settings.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Labels and values of the configured readouts for `tetris`.
+    pub fn readouts<R: Randomizer>(&self, tetris: &Tetris<R>) -> Vec<(&'static str, String)> {
+        self.settings
+            .readouts
+            .iter()
+            .map(|r| (r.label(), r.value(tetris)))
+            .collect()
+    }
+
+    /// Call with the events of a step once `tetris` has been stepped.
+    pub fn handle<R: Randomizer>(&mut self, event: &Event, tetris: &Tetris<R>) {
+        if !self.settings.callouts {
+            return;
+        }
+        match event {
+            Event::TSpin { lines } => {
+                let name = ["T-SPIN", "T-SPIN SINGLE", "T-SPIN DOUBLE", "T-SPIN TRIPLE"]
+                    .get(*lines as usize)
+                    .unwrap_or(&"T-SPIN");
+                self.start(vec![Callout {
+                    text: name.to_string(),
+                    color: Color::MAGENTA,
+                }]);
+                self.t_spin = *lines > 0;
+            }
+            Event::LinesCleared { rows, combo } => {
+                let mut callouts = vec![];
+                if self.t_spin {
+                    callouts.append(&mut self.callouts);
+                } else {
+                    let name = ["SINGLE", "DOUBLE", "TRIPLE"]
+                        .get(rows.len().saturating_sub(1))
+                        .unwrap_or(&"TETRIS");
+                    let color = if rows.len() >= 4 {
+                        Color::SKYBLUE
+                    } else {
+                        Color::WHITE
+                    };
+                    callouts.push(Callout {
+                        text: name.to_string(),
+                        color,
+                    });
+                }
+                let back_to_back = tetris.stats().back_to_back;
+                if back_to_back > 1 {
+                    callouts.push(Callout {
+                        text: format!("BACK-TO-BACK x{}", back_to_back - 1),
+                        color: Color::GOLD,
+                    });
+                }
+                if *combo > 0 {
+                    callouts.push(Callout {
+                        text: format!("{combo} COMBO"),
+                        color: Color::ORANGE,
+                    });
+                }
+                if tetris.board().is_empty() {
+                    callouts.push(Callout {
+                        text: "PERFECT CLEAR".to_owned(),
+                        color: Color::GOLD,
+                    });
+                }
+                self.start(callouts);
+                self.t_spin = false;
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, callouts: Vec<Callout>) {
+        self.callouts = callouts;
+        self.frame = 0;
+    }
+
+    /// Advances the callouts by one frame.
+    pub fn update(&mut self) {
+        if self.callouts.is_empty() {
+            return;
+        }
+        self.frame += 1;
+        if self.frame >= CALLOUT_FRAMES {
+            self.callouts.clear();
+            self.t_spin = false;
+        }
+    }
+
+    /// Draws the callouts over the upper third of the playfield, rising as they fade.
+    pub fn draw_callouts(
+        &self,
+        draw: &mut RaylibDrawHandle,
+        playfield: &Area,
+        cell_size: f32,
+        board_width: i32,
+    ) {
+        let progress = self.frame as f32 / CALLOUT_FRAMES as f32;
+        let alpha = ((1.0 - progress) * 255.0) as u8;
+        let centre = playfield.x() + board_width as f32 * cell_size / 2.0;
+        let mut y = playfield.y() + cell_size * (6.0 - progress);
+        for (i, callout) in self.callouts.iter().enumerate() {
+            // The first line names the clear, the rest are smaller.
+            let size = if i == 0 { cell_size } else { cell_size * 0.7 };
+            let size = (size as i32).max(10);
+            let text_w = draw.measure_text(&callout.text, size);
+            let color = Color::new(callout.color.r, callout.color.g, callout.color.b, alpha);
+            draw.draw_text(
+                &callout.text,
+                centre as i32 - text_w / 2,
+                y as i32,
+                size,
+                color,
+            );
+            y += size as f32 * 1.3;
+        }
+    }
+}
+
+/// Draws each readout as a small label over its value, centred in `area`. Rows shrink from
+/// their usual size so all of them fit.
+pub fn draw_readouts(
+    draw: &mut RaylibDrawHandle,
+    area: &Area,
+    cell_size: f32,
+    readouts: &[(&str, String)],
+) {
+    if readouts.is_empty() {
+        return;
+    }
+    let (left, top, right, bottom) = area.bounds();
+    let column = (left + right) / 2.0;
+    let row = (cell_size * 1.6).min((bottom - top) / readouts.len() as f32);
+    let label_size = ((row * 0.35) as i32).max(8);
+    let value_size = ((row * 0.55) as i32).max(10);
+    for (i, (label, value)) in readouts.iter().enumerate() {
+        let y = top + i as f32 * row;
+        let label_w = draw.measure_text(label, label_size);
+        draw.draw_text(
+            label,
+            column as i32 - label_w / 2,
+            y as i32,
+            label_size,
+            Color::LIGHTGRAY,
+        );
+        let value_w = draw.measure_text(value, value_size);
+        draw.draw_text(
+            value,
+            column as i32 - value_w / 2,
+            y as i32 + label_size,
+            value_size,
+            Color::WHITE,
+        );
+    }
+}
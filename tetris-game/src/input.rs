@@ -0,0 +1,273 @@
+use raylib::prelude::*;
+use tetris_core::action::Action;
+
+use crate::settings::Settings;
+
+const GAMEPAD: i32 = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameAction {
+    MoveLeft,
+    MoveRight,
+    /// Move left until hitting something, used when ARR is 0.
+    ShiftLeft,
+    ShiftRight,
+    SoftDrop,
+    HardDrop,
+    RotateLeft,
+    RotateRight,
+    Hold,
+    /// Takes back the last piece in practice and puzzles.
+    Undo,
+    /// Places again the piece last taken back.
+    Redo,
+    Restart,
+    Pause,
+}
+
+impl GameAction {
+    /// The core action this stands for, if it acts on the game itself.
+    pub fn action(&self) -> Option<Action> {
+        match self {
+            GameAction::MoveLeft => Some(Action::MoveLeft),
+            GameAction::MoveRight => Some(Action::MoveRight),
+            GameAction::ShiftLeft => Some(Action::ShiftLeft),
+            GameAction::ShiftRight => Some(Action::ShiftRight),
+            GameAction::SoftDrop => Some(Action::SoftDrop),
+            GameAction::HardDrop => Some(Action::HardDrop),
+            GameAction::RotateLeft => Some(Action::RotateLeft),
+            GameAction::RotateRight => Some(Action::RotateRight),
+            GameAction::Hold => Some(Action::Hold),
+            GameAction::Undo | GameAction::Redo | GameAction::Restart | GameAction::Pause => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuInput {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+}
+
+/// Delayed auto shift for a single held direction.
+#[derive(Debug, Default, Clone, Copy)]
+struct Repeat {
+    held: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fire {
+    Once,
+    ToWall,
+}
+
+impl Repeat {
+    fn update(&mut self, down: bool, das: u32, arr: u32) -> Option<Fire> {
+        if !down {
+            self.held = 0;
+            return None;
+        }
+
+        self.held += 1;
+        if self.held == 1 {
+            return Some(Fire::Once);
+        }
+        if self.held <= das {
+            return None;
+        }
+        if arr == 0 {
+            Some(Fire::ToWall)
+        } else if (self.held - das).is_multiple_of(arr) {
+            Some(Fire::Once)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct GameInput {
+    das: u32,
+    arr: u32,
+    left: Repeat,
+    right: Repeat,
+    down: Repeat,
+    /// Directions held at the last sample.
+    held: (bool, bool, bool),
+    /// Presses sampled but not yet handed to a simulation step.
+    pressed: Vec<GameAction>,
+    /// Actions of the last step that came from a key going down rather than auto repeat.
+    presses: Vec<GameAction>,
+}
+
+impl GameInput {
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            das: settings.das,
+            arr: settings.arr,
+            ..Default::default()
+        }
+    }
+
+    /// Reads keyboard and gamepad, once per rendered frame.
+    pub fn sample(&mut self, rl: &RaylibHandle) {
+        let pad = rl.is_gamepad_available(GAMEPAD);
+        let key_down = |key, button| {
+            rl.is_key_down(key) || (pad && rl.is_gamepad_button_down(GAMEPAD, button))
+        };
+        let pressed = |keys: &[KeyboardKey], button| {
+            keys.iter().any(|&k| rl.is_key_pressed(k))
+                || (pad && rl.is_gamepad_button_pressed(GAMEPAD, button))
+        };
+
+        self.held = (
+            key_down(
+                KeyboardKey::KEY_LEFT,
+                GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT,
+            ),
+            key_down(
+                KeyboardKey::KEY_RIGHT,
+                GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT,
+            ),
+            key_down(
+                KeyboardKey::KEY_DOWN,
+                GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN,
+            ),
+        );
+
+        let bindings = [
+            (
+                &[KeyboardKey::KEY_SPACE][..],
+                GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP,
+                GameAction::HardDrop,
+            ),
+            (
+                &[KeyboardKey::KEY_A],
+                GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT,
+                GameAction::RotateLeft,
+            ),
+            (
+                &[KeyboardKey::KEY_D],
+                GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN,
+                GameAction::RotateRight,
+            ),
+            (
+                &[KeyboardKey::KEY_S],
+                GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_1,
+                GameAction::Hold,
+            ),
+            (
+                &[KeyboardKey::KEY_Z],
+                GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_UP,
+                GameAction::Undo,
+            ),
+            (
+                &[KeyboardKey::KEY_Y],
+                GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_1,
+                GameAction::Redo,
+            ),
+            (
+                &[KeyboardKey::KEY_R],
+                GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT,
+                GameAction::Restart,
+            ),
+            (
+                &[KeyboardKey::KEY_ESCAPE, KeyboardKey::KEY_P],
+                GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT,
+                GameAction::Pause,
+            ),
+        ];
+        for (keys, button, action) in bindings {
+            if pressed(keys, button) {
+                self.pressed.push(action);
+            }
+        }
+    }
+
+    /// The actions for one simulation step: held directions repeat per step, presses from
+    /// the last sample go to the first step after it.
+    pub fn step(&mut self) -> Vec<GameAction> {
+        let mut actions = vec![];
+        let (left, right, down) = self.held;
+
+        match self.left.update(left, self.das, self.arr) {
+            Some(Fire::Once) => actions.push(GameAction::MoveLeft),
+            Some(Fire::ToWall) => actions.push(GameAction::ShiftLeft),
+            None => {}
+        }
+        match self.right.update(right, self.das, self.arr) {
+            Some(Fire::Once) => actions.push(GameAction::MoveRight),
+            Some(Fire::ToWall) => actions.push(GameAction::ShiftRight),
+            None => {}
+        }
+        if self.down.update(down, self.das, self.arr).is_some() {
+            actions.push(GameAction::SoftDrop);
+        }
+
+        self.presses.clear();
+        for (repeat, action) in [
+            (self.left, GameAction::MoveLeft),
+            (self.right, GameAction::MoveRight),
+            (self.down, GameAction::SoftDrop),
+        ] {
+            if repeat.held == 1 {
+                self.presses.push(action);
+            }
+        }
+        self.presses.extend_from_slice(&self.pressed);
+
+        actions.append(&mut self.pressed);
+        actions
+    }
+
+    /// The keys newly pressed in the last step.
+    pub fn presses(&self) -> &[GameAction] {
+        &self.presses
+    }
+}
+
+/// Returns the menu navigation pressed this frame, if any.
+pub fn menu_input(rl: &RaylibHandle) -> Option<MenuInput> {
+    let pad = rl.is_gamepad_available(GAMEPAD);
+    let bindings = [
+        (
+            KeyboardKey::KEY_UP,
+            GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP,
+            MenuInput::Up,
+        ),
+        (
+            KeyboardKey::KEY_DOWN,
+            GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN,
+            MenuInput::Down,
+        ),
+        (
+            KeyboardKey::KEY_LEFT,
+            GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT,
+            MenuInput::Left,
+        ),
+        (
+            KeyboardKey::KEY_RIGHT,
+            GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT,
+            MenuInput::Right,
+        ),
+        (
+            KeyboardKey::KEY_ENTER,
+            GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN,
+            MenuInput::Confirm,
+        ),
+        (
+            KeyboardKey::KEY_ESCAPE,
+            GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT,
+            MenuInput::Back,
+        ),
+    ];
+
+    bindings.into_iter().find_map(|(key, button, input)| {
+        (rl.is_key_pressed(key) || (pad && rl.is_gamepad_button_pressed(GAMEPAD, button)))
+            .then_some(input)
+    })
+}
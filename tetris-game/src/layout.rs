@@ -0,0 +1,251 @@
+use crate::area::Area;
+
+/// Cells left of the playfield, for the next and hold boxes and the readouts.
+const LEFT_COLUMN: f32 = 6.0;
+/// Cells right of the playfield, for the rest of the queue at half size.
+const RIGHT_COLUMN: f32 = 4.0;
+/// Cells of height the left column needs, even next to a shorter board.
+const COLUMN_ROWS: f32 = 20.0;
+/// Cells between the tops of two boxes in the queue.
+const QUEUE_STEP: f32 = 3.0;
+/// Cells of height of a box in the queue, drawn at half size.
+const QUEUE_BOX: f32 = 2.5;
+
+/// Where everything of one board goes on screen, in pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BoardLayout {
+    pub cell_size: f32,
+    pub playfield: Area,
+    /// Top left corner of the next box, label included.
+    pub next: (f32, f32),
+    pub hold: (f32, f32),
+    /// Below the hold box, for the readouts or the finesse trainer.
+    pub hud: Area,
+    /// Boxes of the rest of the queue that fit the column, pieces past them are not shown.
+    pub queue_slots: usize,
+    /// Top left corner of the second piece of the queue, the first one goes in the next box.
+    queue: (f32, f32),
+}
+
+impl BoardLayout {
+    /// Top left corner of the `i`th box of the rest of the queue.
+    pub fn queue_slot(&self, i: usize) -> (f32, f32) {
+        let (x, y) = self.queue;
+        (x, y + i as f32 * QUEUE_STEP * self.cell_size)
+    }
+}
+
+/// Lays out boards of the given sizes in columns next to each other, all with the same
+/// cell size, as large as fits the `(width, height)` of the window.
+pub fn boards((width, height): (f32, f32), sizes: &[(i32, i32)]) -> Vec<BoardLayout> {
+    if sizes.is_empty() {
+        return vec![];
+    }
+    let slot_width = width / sizes.len() as f32;
+    let cell_size = sizes
+        .iter()
+        .map(|&(w, h)| {
+            let cells = (LEFT_COLUMN + w as f32 + RIGHT_COLUMN, rows(h));
+            (slot_width / cells.0).min(height / cells.1)
+        })
+        .fold(f32::INFINITY, f32::min)
+        .max(0.0);
+
+    sizes
+        .iter()
+        .enumerate()
+        .map(|(i, &(w, h))| {
+            let block = (
+                (LEFT_COLUMN + w as f32 + RIGHT_COLUMN) * cell_size,
+                rows(h) * cell_size,
+            );
+            let x = i as f32 * slot_width + (slot_width - block.0) / 2.0;
+            let y = (height - block.1) / 2.0;
+            board(cell_size, (x, y), (w, h))
+        })
+        .collect()
+}
+
+/// Rows of cells a board of height `h` needs with its columns.
+fn rows(h: i32) -> f32 {
+    (h as f32).max(COLUMN_ROWS)
+}
+
+/// The layout of a board of `(w, h)` cells whose columns start at `(x, y)`.
+fn board(cell_size: f32, (x, y): (f32, f32), (w, h): (i32, i32)) -> BoardLayout {
+    let cell = |n: f32| n * cell_size;
+    // Short boards sit in the middle of their columns.
+    let playfield = Area::new(
+        x + cell(LEFT_COLUMN),
+        y + cell((rows(h) - h as f32) / 2.0),
+        cell(w as f32),
+        cell(h as f32),
+    );
+    let column = x + cell(LEFT_COLUMN / 2.0);
+    // The queue starts two cells down, like the next box, and stops at the column's end.
+    let queue_slots = ((rows(h) - 2.0 - QUEUE_BOX) / QUEUE_STEP + 1.0).max(0.0) as usize;
+    BoardLayout {
+        cell_size,
+        playfield,
+        next: (column - cell(2.0), y + cell(2.0)),
+        hold: (column - cell(2.0), y + cell(8.0)),
+        hud: Area::new(x, y + cell(14.0), cell(LEFT_COLUMN), cell(rows(h) - 14.0)),
+        queue_slots,
+        queue: (playfield.x_end() + cell(1.0), y + cell(2.0)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tetris_core::traits::HasSize;
+
+    const WINDOWS: &[(f32, f32)] = &[
+        (920.0, 720.0),
+        (1920.0, 1080.0),
+        (480.0, 1000.0),
+        (300.0, 200.0),
+    ];
+    const SIZES: &[(i32, i32)] = &[(10, 20), (4, 8), (40, 8), (8, 40), (40, 40)];
+
+    fn contains(outer: &Area, inner: &Area) -> bool {
+        let epsilon = 0.01;
+        inner.x() >= outer.x() - epsilon
+            && inner.y() >= outer.y() - epsilon
+            && inner.x_end() <= outer.x_end() + epsilon
+            && inner.y_end() <= outer.y_end() + epsilon
+    }
+
+    fn overlaps(a: &Area, b: &Area) -> bool {
+        a.x() < b.x_end() && b.x() < a.x_end() && a.y() < b.y_end() && b.y() < a.y_end()
+    }
+
+    /// Everything drawn for a board with `preview` pieces in the queue: the playfield, the
+    /// next and hold boxes, the readouts and the half size boxes of the queue that fit.
+    fn parts(layout: &BoardLayout, preview: usize) -> Vec<Area> {
+        let c = layout.cell_size;
+        let mut parts = vec![
+            layout.playfield,
+            Area::new(layout.next.0, layout.next.1, c * 4.0, c * 5.0),
+            Area::new(layout.hold.0, layout.hold.1, c * 4.0, c * 5.0),
+            layout.hud,
+        ];
+        for i in 0..preview.saturating_sub(1).min(layout.queue_slots) {
+            let (x, y) = layout.queue_slot(i);
+            parts.push(Area::new(x, y, c * 2.0, c * QUEUE_BOX));
+        }
+        parts
+    }
+
+    #[test]
+    fn area_ends_add_size_to_position() {
+        let area = Area::new(10.0, 20.0, 30.0, 40.0);
+        assert_eq!(area.bounds(), (10.0, 20.0, 40.0, 60.0));
+        assert_eq!(area.offset((1.0, -2.0)).bounds(), (11.0, 18.0, 41.0, 58.0));
+    }
+
+    #[test]
+    fn everything_fits_the_window() {
+        for &window in WINDOWS {
+            let screen = Area::new(0.0, 0.0, window.0, window.1);
+            for &size in SIZES {
+                for count in 1..=2 {
+                    for layout in boards(window, &vec![size; count]) {
+                        for part in parts(&layout, 5) {
+                            assert!(
+                                contains(&screen, &part),
+                                "{part:?} of {size:?} x{count} is off a {window:?} window"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parts_do_not_overlap() {
+        for &window in WINDOWS {
+            for &size in SIZES {
+                let layouts = boards(window, &[size, size]);
+                let all: Vec<Area> = layouts.iter().flat_map(|l| parts(l, 5)).collect();
+                for (i, a) in all.iter().enumerate() {
+                    for b in &all[i + 1..] {
+                        assert!(
+                            !overlaps(a, b),
+                            "{a:?} and {b:?} overlap for {size:?} in a {window:?} window"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cells_fill_the_tighter_dimension() {
+        // A standard board in a wide window is limited by the height.
+        let wide = boards((1920.0, 1080.0), &[(10, 20)]);
+        assert_eq!(wide[0].cell_size, 1080.0 / 20.0);
+        assert_eq!(wide[0].playfield.y(), 0.0);
+
+        // In a portrait window the columns and playfield take the whole width.
+        let tall = boards((480.0, 1000.0), &[(10, 20)]);
+        assert_eq!(tall[0].cell_size, 480.0 / 20.0);
+        assert_eq!(tall[0].hud.x(), 0.0);
+        assert_eq!(tall[0].playfield.y(), (1000.0 - 20.0 * 24.0) / 2.0);
+    }
+
+    #[test]
+    fn versus_boards_share_a_cell_size_and_split_the_width() {
+        let layouts = boards((1920.0, 1080.0), &[(10, 20), (10, 20)]);
+        assert_eq!(layouts[0].cell_size, layouts[1].cell_size);
+        assert!(layouts[0].queue_slot(0).0 < 960.0);
+        assert!(layouts[1].hud.x() >= 960.0);
+        assert_eq!(
+            layouts[1].playfield.x() - layouts[0].playfield.x(),
+            960.0,
+            "both boards sit at the same place in their half"
+        );
+    }
+
+    #[test]
+    fn short_boards_keep_room_for_the_columns() {
+        let layout = boards((920.0, 720.0), &[(10, 8)])[0];
+        assert_eq!(layout.cell_size, 720.0 / COLUMN_ROWS);
+        assert!(layout.hud.y_end() <= 720.0);
+        assert_eq!(layout.playfield.height(), (8.0 * layout.cell_size) as i32);
+    }
+
+    #[test]
+    fn previews_fit_a_short_window() {
+        let window = (920.0, 240.0);
+        let screen = Area::new(0.0, 0.0, window.0, window.1);
+        for preview in [0, 7] {
+            for &size in SIZES {
+                let layout = boards(window, &[size])[0];
+                let all = parts(&layout, preview);
+                for (i, a) in all.iter().enumerate() {
+                    assert!(contains(&screen, a), "{a:?} of {size:?} is off the window");
+                    for b in &all[i + 1..] {
+                        assert!(!overlaps(a, b), "{a:?} and {b:?} overlap for {size:?}");
+                    }
+                }
+            }
+        }
+        // A standard board has room for all seven, the first in the next box.
+        let layout = boards(window, &[(10, 20)])[0];
+        assert_eq!(layout.queue_slots, 6);
+    }
+
+    #[test]
+    fn long_queues_stop_at_the_end_of_the_column() {
+        for &size in SIZES {
+            let layout = boards((480.0, 1000.0), &[size])[0];
+            let (_, y) = layout.queue_slot(layout.queue_slots - 1);
+            let column_end = layout.hud.y_end();
+            assert!(y + QUEUE_BOX * layout.cell_size <= column_end + 0.01);
+            let (_, past) = layout.queue_slot(layout.queue_slots);
+            assert!(past + QUEUE_BOX * layout.cell_size > column_end, "{size:?}");
+        }
+    }
+}
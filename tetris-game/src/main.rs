@@ -1,20 +1,53 @@
 use area::Area;
-use audio_box::{ROTATE_SOUND_BYTES, WRONG_MOVE_SOUND_BYTES};
+use audio_box::{AudioBox, Category};
 use clap::Parser;
 use config::Config;
+use cpu::Cpu;
+use effects::Effects;
+use finesse::Finesse;
+use hud::{draw_readouts, Hud};
+use input::{menu_input, GameAction, GameInput, MenuInput};
+use layout::BoardLayout;
+use openers::Guide;
+use records::Record;
+use replay::ReplayFile;
+use scene::{dim, draw_caption, draw_panel, GameMode, OptionsMenu, Scene, Transition};
+use settings::Settings;
+use skin::Skin;
+use spectator::{Snapshot, SpectatorClient, SpectatorServer};
 use tetris_core::{
     cell::Cell,
-    prelude::{Color as TetrisColor, Tetris},
+    event::Event,
+    history::{self, History},
+    prelude::{Action, PieceSet, Player, Replay, Ruleset, Tetris},
+    puzzle::{PuzzleRun, Status},
     traits::{HasSize, IterateDimensions, Randomizer},
 };
+use timestep::FixedTimestep;
 
 mod area;
 mod audio_box;
 mod config;
+mod cpu;
+mod effects;
+mod finesse;
+mod hud;
+mod input;
+mod layout;
+mod openers;
+mod puzzles;
+mod records;
+mod replay;
+mod scene;
+mod settings;
+mod skin;
+mod spectator;
+mod timestep;
 
 use raylib::prelude::*;
 
-pub static BRICK_IMAGE: &[u8] = include_bytes!("../brick.png");
+/// Placements that can be taken back in practice mode.
+const PRACTICE_HISTORY: usize = 100;
 
 #[derive(Debug, Clone, Default)]
 struct DummyRng(i32);
@@ -35,92 +68,801 @@ impl Randomizer for DummyRng {
     }
 }
 
+/// The bot's board in a versus game.
+struct Opponent {
+    tetris: Tetris<DummyRng>,
+    cpu: Cpu,
+    effects: Effects,
+    hud: Hud,
+    /// The piece in play before the latest step, to draw it on its way down.
+    before: Option<Player>,
+}
+
+struct Game {
+    mode: GameMode,
+    tetris: Tetris<DummyRng>,
+    input: GameInput,
+    effects: Effects,
+    hud: Hud,
+    /// Plays instead of the player when watching the bot.
+    cpu: Option<Cpu>,
+    opponent: Option<Opponent>,
+    /// Garbage rows sent to the bot in versus.
+    attack: u32,
+    bot_delay: u32,
+    finesse: Finesse,
+    puzzle: Option<PuzzleRun<DummyRng>>,
+    /// The game before every piece in practice mode.
+    history: Option<History<Tetris<DummyRng>>>,
+    /// The opener suggested in practice mode.
+    guide: Option<Guide>,
+    /// Inputs applied so far, by simulation frame.
+    replay: Replay,
+    /// Inputs to apply instead of the player's when watching a replay.
+    playback: Option<Replay>,
+    /// The piece in play before the latest step, to draw it on its way down.
+    before: Option<Player>,
+}
+
+impl Game {
+    fn new(
+        mode: GameMode,
+        settings: &Settings,
+        config: &Config,
+        ruleset: &Ruleset,
+    ) -> Result<Self, String> {
+        if config.record.is_some() && mode.undoable() {
+            return Err("pieces taken back cannot be recorded, drop --record".to_owned());
+        }
+        let seed = config.seed.unwrap_or(rand::random());
+        let scale = mode.board_scale();
+        let (tetris, puzzle) = setup(
+            mode,
+            config.width.unwrap_or(settings.width) as i32 * scale,
+            config.height.unwrap_or(settings.height) as i32 * scale,
+            ruleset,
+            seed,
+        )?;
+        Ok(Self::start(
+            mode,
+            tetris,
+            puzzle,
+            settings,
+            settings.bot_delay,
+            Replay::new(seed),
+            None,
+        ))
+    }
+
+    fn from_replay(file: &ReplayFile, settings: &Settings) -> Result<Self, String> {
+        let seed = file.replay.seed;
+        let (tetris, puzzle) = setup(file.mode, file.width, file.height, &file.ruleset, seed)?;
+        let playback = Some(file.replay.clone());
+        Ok(Self::start(
+            file.mode,
+            tetris,
+            puzzle,
+            settings,
+            file.bot_delay,
+            Replay::new(seed),
+            playback,
+        ))
+    }
+
+    fn start(
+        mode: GameMode,
+        tetris: Tetris<DummyRng>,
+        puzzle: Option<PuzzleRun<DummyRng>>,
+        settings: &Settings,
+        bot_delay: u32,
+        replay: Replay,
+        playback: Option<Replay>,
+    ) -> Self {
+        // Both sides of a versus game get the same pieces.
+        let opponent = (mode == GameMode::Versus).then(|| Opponent {
+            tetris: tetris.clone(),
+            cpu: Cpu::new(bot_delay),
+            effects: Effects::new(&settings.effects),
+            hud: Hud::new(&settings.hud),
+            before: None,
+        });
+        let history =
+            (mode == GameMode::Practice).then(|| History::new(tetris.clone(), PRACTICE_HISTORY));
+        let guide = (mode == GameMode::Practice).then(|| {
+            let mut guide = Guide::new();
+            guide.update(&tetris);
+            guide
+        });
+        Self {
+            mode,
+            tetris,
+            input: GameInput::new(settings),
+            effects: Effects::new(&settings.effects),
+            hud: Hud::new(&settings.hud),
+            cpu: (mode == GameMode::Bot).then(|| Cpu::new(bot_delay)),
+            opponent,
+            attack: 0,
+            bot_delay,
+            finesse: if mode == GameMode::Finesse {
+                Finesse::trainer()
+            } else {
+                Finesse::new()
+            },
+            puzzle,
+            history,
+            guide,
+            replay,
+            playback,
+            before: None,
+        }
+    }
+
+    fn replay_file(&self) -> ReplayFile {
+        ReplayFile {
+            mode: self.mode,
+            width: self.tetris.width(),
+            height: self.tetris.height(),
+            ruleset: self.tetris.ruleset().clone(),
+            bot_delay: self.bot_delay,
+            replay: self.replay.clone(),
+        }
+    }
+
+    /// Advances the bot's board by a frame, sending garbage both ways.
+    fn step_opponent(&mut self, events: &[Event]) {
+        let Some(opponent) = &mut self.opponent else {
+            return;
+        };
+        for event in events {
+            if let Event::LinesCleared { rows, .. } = event {
+                let rows = cpu::attack(rows.len());
+                opponent.tetris.add_garbage(rows);
+                self.attack += rows;
+            }
+        }
+
+        opponent.before = Some(opponent.tetris.player());
+        let actions = opponent.cpu.actions(&opponent.tetris);
+        opponent.tetris.count_keys(actions.len() as u32);
+        for action in actions {
+            opponent.tetris.apply(action);
+        }
+        opponent.tetris.tick();
+        for event in opponent.tetris.drain_events() {
+            opponent.effects.handle(&event, opponent.tetris.width());
+            opponent.hud.handle(&event, &opponent.tetris);
+            if let Event::LinesCleared { rows, .. } = &event {
+                self.tetris.add_garbage(cpu::attack(rows.len()));
+            }
+        }
+        opponent.effects.update();
+        opponent.hud.update();
+    }
+
+    /// Takes back the last piece placed, in practice and puzzles.
+    fn undo(&mut self) {
+        if let Some(puzzle) = &mut self.puzzle {
+            puzzle.undo(&mut self.tetris);
+        }
+        if let Some(history) = &mut self.history {
+            self.tetris = history.undo().clone();
+        }
+        if let Some(guide) = &mut self.guide {
+            guide.update(&self.tetris);
+        }
+    }
+
+    fn redo(&mut self) {
+        if let Some(puzzle) = &mut self.puzzle {
+            puzzle.redo(&mut self.tetris);
+        }
+        if let Some(next) = self.history.as_mut().and_then(History::redo) {
+            self.tetris = next.clone();
+        }
+        if let Some(guide) = &mut self.guide {
+            guide.update(&self.tetris);
+        }
+    }
+
+    /// Keeps the undo history and the puzzle goal up to date after a step.
+    fn record(&mut self, events: &[Event]) {
+        if let Some(puzzle) = &mut self.puzzle {
+            puzzle.observe(&self.tetris, events);
+        }
+        if let Some(history) = &mut self.history {
+            if history::placed(history.present(), &self.tetris) {
+                history.push(self.tetris.clone());
+                if let Some(guide) = &mut self.guide {
+                    guide.update(&self.tetris);
+                }
+            }
+        }
+    }
+
+    /// The game so far with its stats.
+    fn results(&self) -> Record {
+        let attack = self.opponent.as_ref().map(|_| self.attack);
+        Record::new(self.mode, &self.tetris, attack)
+    }
+
+    /// Whether the game goes into the stats history: played by hand, start to finish,
+    /// without taking pieces back.
+    fn tracked(&self) -> bool {
+        self.human() && !self.mode.undoable()
+    }
+
+    /// Whether the player's own keys drive the game, not the bot or a replay.
+    fn human(&self) -> bool {
+        self.cpu.is_none() && self.playback.is_none()
+    }
+
+    fn over(&self) -> bool {
+        self.tetris.game_over()
+            || self.opponent.as_ref().is_some_and(|o| o.tetris.game_over())
+            || self.puzzle_status().is_some_and(|s| s != Status::Playing)
+    }
+
+    fn puzzle_status(&self) -> Option<Status> {
+        self.puzzle.as_ref().map(|p| p.status(&self.tetris))
+    }
+
+    fn results_title(&self) -> String {
+        if let Some(status) = self.puzzle_status() {
+            let solved = status == Status::Solved;
+            return if solved { "Solved" } else { "Failed" }.to_owned();
+        }
+        match &self.opponent {
+            Some(_) if self.tetris.game_over() => "You lose".to_owned(),
+            Some(_) => "You win".to_owned(),
+            None => format!("Game over - {:0>5}", self.tetris.score()),
+        }
+    }
+
+    /// Picks up changes made in the options menu while the game was paused.
+    fn apply_settings(&mut self, settings: &Settings) {
+        self.input = GameInput::new(settings);
+        self.effects = Effects::new(&settings.effects);
+        self.hud = Hud::new(&settings.hud);
+        if let Some(opponent) = &mut self.opponent {
+            opponent.effects = Effects::new(&settings.effects);
+            opponent.hud = Hud::new(&settings.hud);
+        }
+    }
+}
+
 pub fn main() {
     dotenvy::dotenv().ok();
     let config = Config::parse();
-    let mut tetris = Tetris::new(
-        config.width as i32,
-        config.height as i32,
-        DummyRng::with_seed(config.seed.unwrap_or(rand::random())),
-    );
+    if let Some(addr) = &config.spectate {
+        spectate(addr);
+        return;
+    }
+
+    let mut server = match config.serve.as_deref().map(SpectatorServer::bind) {
+        Some(Ok(server)) => Some(server),
+        Some(Err(e)) => {
+            eprintln!("Could not start spectator server: {e}");
+            return;
+        }
+        None => None,
+    };
+
+    let ruleset = match config.ruleset() {
+        Ok(ruleset) => ruleset,
+        Err(e) => {
+            eprintln!("Could not load ruleset: {e}");
+            return;
+        }
+    };
+
+    let replay_file = match config.replay.as_deref().map(ReplayFile::load) {
+        Some(Ok(file)) => Some(file),
+        Some(Err(e)) => {
+            eprintln!("Could not load replay: {e}");
+            return;
+        }
+        None => None,
+    };
+
+    let mut settings = Settings::load();
+    if config.width.is_some() || config.height.is_some() {
+        let width = config.width.unwrap_or(settings.width) as i32;
+        let height = config.height.unwrap_or(settings.height) as i32;
+        if let Err(e) = ruleset.check_board(width, height, &PieceSet::tetrominoes()) {
+            eprintln!("Invalid board size: {e}");
+            return;
+        }
+    }
+
     let (mut rl, thread) = raylib::init()
         .size(920, 720)
         .title("Tetris")
         .resizable()
+        .vsync()
         .build();
+    rl.set_exit_key(None);
 
-    let brick_image = Image::load_image_from_mem(".png", BRICK_IMAGE).unwrap();
-    let brick_texture = rl.load_texture_from_image(&thread, &brick_image).unwrap();
-
-    // TODO: let mut audio = AudioBox::new();
-    // audio.load_sound_from_bytes("rotate", ROTATE_SOUND_BYTES, ".ogg");
-    // audio.load_sound_from_bytes("rotate", ROTATE_SOUND_BYTES, ".ogg");
-    // audio.load_sound_from_bytes("wrong", WRONG_MOVE_SOUND_BYTES, ".ogg");
-
-    let audio = RaylibAudio::init_audio_device().unwrap();
-    let rotate_sound = audio
-        .new_wave_from_memory(".ogg", ROTATE_SOUND_BYTES)
-        .unwrap();
-    let wrong_move_sound = audio
-        .new_wave_from_memory(".ogg", WRONG_MOVE_SOUND_BYTES)
-        .unwrap();
+    let mut skin = Skin::load(&mut rl, &thread, &settings.skin);
 
-    let rotate_sound = audio.new_sound_from_wave(&rotate_sound).unwrap();
-    let wrong_move_sound = audio.new_sound_from_wave(&wrong_move_sound).unwrap();
+    let audio_subsystem = RaylibAudio::init_audio_device().unwrap();
+    let sound_pack = config.sound_pack.clone().or(settings.sound_pack.clone());
+    let mut audio = AudioBox::new(&audio_subsystem, sound_pack.as_deref());
+    audio.play_music();
 
-    let mut playfield = Area::default();
+    let mut scenes = vec![Scene::title()];
+    let mut game: Option<Game> = None;
+    let mut timestep = FixedTimestep::default();
 
-    rl.set_target_fps(60);
+    if let Some(file) = &replay_file {
+        match Game::from_replay(file, &settings) {
+            Ok(replay) => game = Some(replay),
+            Err(e) => {
+                eprintln!("Could not play replay: {e}");
+                return;
+            }
+        }
+        scenes.push(Scene::Playing);
+    }
+    // Why the last game could not start, shown until the next change of scene.
+    let mut notice: Option<String> = None;
 
     while !rl.window_should_close() {
         let (width, height) = (rl.get_screen_width(), rl.get_screen_height());
-        handle_events(&mut tetris, &mut rl, &rotate_sound, &wrong_move_sound);
+        let menu = menu_input(&rl);
+        audio_subsystem.set_master_volume(settings.volume);
+        audio.set_volume(Category::Sfx, settings.sfx_volume);
+        audio.set_volume(Category::Music, settings.music_volume);
+        audio.set_volume(Category::Ui, settings.ui_volume);
+        audio.pause_music(game.is_some() && !matches!(scenes.last(), Some(Scene::Playing)));
+        audio.update();
 
-        let cell_size = resize_playfield(
-            (width, height),
-            tetris.width(),
-            tetris.height(),
-            &mut playfield,
-        );
+        if !matches!(scenes.last(), Some(Scene::Playing)) {
+            match menu {
+                Some(MenuInput::Confirm) => {
+                    audio.play_sound("menu_confirm");
+                }
+                Some(_) => {
+                    audio.play_sound("menu_move");
+                }
+                None => {}
+            }
+        }
 
-        tetris.tick();
+        if !matches!(scenes.last(), Some(Scene::Playing)) {
+            timestep.reset();
+        }
+
+        let transition = match scenes.last_mut() {
+            Some(Scene::Title(m)) => match menu.and_then(|i| m.navigate(i)) {
+                Some(0) => Transition::Push(Scene::mode_select()),
+                Some(1) => Transition::Push(Scene::Options(OptionsMenu::default())),
+                Some(_) => Transition::Quit,
+                None => Transition::None,
+            },
+            Some(Scene::ModeSelect(m)) => match menu {
+                Some(MenuInput::Back) => Transition::Pop,
+                Some(i) => match m.navigate(i).map(|idx| GameMode::ALL[idx]) {
+                    Some(GameMode::Puzzle(_)) => Transition::Push(Scene::puzzle_select()),
+                    Some(mode) => Transition::Start(mode),
+                    None => Transition::None,
+                },
+                None => Transition::None,
+            },
+            Some(Scene::PuzzleSelect(m)) => match menu {
+                Some(MenuInput::Back) => Transition::Pop,
+                Some(i) => m.navigate(i).map_or(Transition::None, |idx| {
+                    Transition::Start(GameMode::Puzzle(idx))
+                }),
+                None => Transition::None,
+            },
+            Some(Scene::Options(o)) => {
+                if menu.is_some_and(|i| o.navigate(i, &mut settings)) {
+                    if let Err(e) = settings.save() {
+                        eprintln!("Could not save settings: {e}");
+                    }
+                    if let Some(game) = &mut game {
+                        game.apply_settings(&settings);
+                    }
+                    Transition::Pop
+                } else {
+                    Transition::None
+                }
+            }
+            Some(Scene::Playing) => match &mut game {
+                Some(game) => {
+                    game.input.sample(&rl);
+                    let mut transition = Transition::None;
+                    for _ in 0..timestep.advance(rl.get_frame_time()) {
+                        let actions = game.input.step();
+                        if actions.contains(&GameAction::Pause) {
+                            transition = Transition::Push(Scene::paused());
+                            break;
+                        }
+                        if actions.contains(&GameAction::Restart) {
+                            transition = Transition::Start(game.mode);
+                            break;
+                        }
+                        if game.human() {
+                            if actions.contains(&GameAction::Undo) {
+                                game.undo();
+                            }
+                            if actions.contains(&GameAction::Redo) {
+                                game.redo();
+                            }
+                        }
+
+                        game.before = Some(game.tetris.player());
+                        let actions: Vec<Action> = match (&game.playback, &mut game.cpu) {
+                            (Some(playback), _) => {
+                                playback.actions_at(game.tetris.frame()).collect()
+                            }
+                            (None, Some(cpu)) => cpu.actions(&game.tetris),
+                            (None, None) => actions.iter().filter_map(GameAction::action).collect(),
+                        };
+                        if game.human() {
+                            let presses = game.input.presses();
+                            game.finesse.track(&game.tetris, presses);
+                            let keys = presses.iter().filter(|a| a.action().is_some()).count();
+                            game.tetris.count_keys(keys as u32);
+                        } else if game.cpu.is_some() {
+                            // The bot taps every input, replays do not keep the presses.
+                            game.tetris.count_keys(actions.len() as u32);
+                        }
+                        let applied =
+                            handle_actions(&mut game.tetris, &mut game.replay, &actions, &audio);
+                        if applied.contains(&Action::Hold) {
+                            game.finesse.held();
+                        }
+                        game.tetris.tick();
+                        let events = game.tetris.drain_events();
+                        for event in &events {
+                            game.effects.handle(event, game.tetris.width());
+                            game.hud.handle(event, &game.tetris);
+                            game.finesse.handle(event);
+                            audio.play_event(event);
+                        }
+                        game.effects.update();
+                        game.hud.update();
+                        game.record(&events);
+                        game.step_opponent(&events);
+                        if let Some(server) = &mut server {
+                            server.broadcast(Snapshot::from(&game.tetris));
+                        }
+                        if game.over() {
+                            if let Some(path) = &config.record {
+                                if let Err(e) = game.replay_file().save(path) {
+                                    eprintln!("Could not save replay: {e}");
+                                }
+                            }
+                            if game.tracked() {
+                                if let Err(e) = game.results().append() {
+                                    eprintln!("Could not save stats: {e}");
+                                }
+                            }
+                            transition = Transition::Replace(Scene::results());
+                            break;
+                        }
+                    }
+                    transition
+                }
+                None => Transition::Title,
+            },
+            Some(Scene::Paused(m)) => match menu {
+                Some(MenuInput::Back) => Transition::Pop,
+                Some(i) => match m.navigate(i) {
+                    Some(0) => Transition::Pop,
+                    Some(1) => game
+                        .as_ref()
+                        .map_or(Transition::Title, |g| Transition::Start(g.mode)),
+                    Some(2) => Transition::Push(Scene::Options(OptionsMenu::default())),
+                    Some(_) => Transition::Title,
+                    None => Transition::None,
+                },
+                None => Transition::None,
+            },
+            Some(Scene::Results(m)) => match menu.and_then(|i| m.navigate(i)) {
+                Some(0) => game
+                    .as_ref()
+                    .map_or(Transition::Title, |g| Transition::Start(g.mode)),
+                Some(_) => Transition::Title,
+                None => Transition::None,
+            },
+            None => Transition::Quit,
+        };
+
+        if !matches!(transition, Transition::None) {
+            notice = None;
+        }
+        match transition {
+            Transition::None => {}
+            Transition::Push(scene) => scenes.push(scene),
+            Transition::Pop => {
+                scenes.pop();
+            }
+            Transition::Replace(scene) => {
+                scenes.pop();
+                scenes.push(scene);
+            }
+            Transition::Start(mode) => {
+                let started = match &replay_file {
+                    Some(file) => Game::from_replay(file, &settings),
+                    None => Game::new(mode, &settings, &config, &ruleset),
+                };
+                match started {
+                    Ok(started) => {
+                        game = Some(started);
+                        scenes.truncate(1);
+                        scenes.push(Scene::Playing);
+                    }
+                    Err(e) => notice = Some(format!("Cannot start {}: {e}", mode.name())),
+                }
+            }
+            Transition::Title => {
+                game = None;
+                scenes = vec![Scene::title()];
+            }
+            Transition::Quit => break,
+        }
+
+        if skin.name() != settings.skin {
+            skin = Skin::load(&mut rl, &thread, &settings.skin);
+        }
 
         let mut draw = rl.begin_drawing(&thread);
         draw.clear_background(Color::new(0, 44, 88, 255));
-        draw_playfield(&playfield, &tetris, &mut draw, cell_size, &brick_texture);
+        skin.draw_background(&mut draw);
 
-        draw_boxed(
-            (playfield.x() / 2.0 - cell_size * 2.0, cell_size * 2.0),
+        if let Some(game) = game
+            .as_ref()
+            .filter(|_| scenes.iter().any(Scene::shows_game))
+        {
+            // Versus splits the screen, the bot's board on the right.
+            let mut sizes = vec![(game.tetris.width(), game.tetris.height())];
+            if let Some(opponent) = &game.opponent {
+                sizes.push((opponent.tetris.width(), opponent.tetris.height()));
+            }
+            let layouts = layout::boards((width as f32, height as f32), &sizes);
+            let layout = &layouts[0];
+            if let Some(opponent) = &game.opponent {
+                let layout = &layouts[1];
+                draw_game(
+                    layout,
+                    opponent.tetris.board(),
+                    falling(&opponent.tetris, opponent.before, timestep.alpha()),
+                    opponent.tetris.queue(),
+                    opponent.tetris.held(),
+                    &opponent.hud.readouts(&opponent.tetris),
+                    &mut draw,
+                    &skin,
+                    Some(&opponent.effects),
+                    timestep.alpha(),
+                );
+                opponent.hud.draw_callouts(
+                    &mut draw,
+                    &layout.playfield,
+                    layout.cell_size,
+                    opponent.tetris.width(),
+                );
+            }
+            // The finesse trainer's panel takes the place of the readouts.
+            let readouts = if game.mode == GameMode::Finesse {
+                vec![]
+            } else {
+                game.hud.readouts(&game.tetris)
+            };
+            draw_game(
+                layout,
+                game.tetris.board(),
+                falling(&game.tetris, game.before, timestep.alpha()),
+                game.tetris.queue(),
+                game.tetris.held(),
+                &readouts,
+                &mut draw,
+                &skin,
+                Some(&game.effects),
+                timestep.alpha(),
+            );
+            game.hud.draw_callouts(
+                &mut draw,
+                &layout.playfield,
+                layout.cell_size,
+                game.tetris.width(),
+            );
+            if game.mode == GameMode::Finesse {
+                draw_finesse(layout, &game.finesse, &mut draw);
+            }
+            if let Some(puzzle) = &game.puzzle {
+                let name = &game.tetris.ruleset().name;
+                draw_caption(
+                    &mut draw,
+                    &format!("{name}: {} - Z undo, Y redo", puzzle.goal),
+                );
+            }
+            if let Some(guide) = &game.guide {
+                draw_guide(layout, guide, &game.tetris, &mut draw, &skin);
+                draw_caption(&mut draw, &format!("{} - Z undo, Y redo", guide.caption()));
+            }
+        }
+
+        match scenes.last() {
+            Some(Scene::Title(m)) => m.draw(&mut draw, "Tetris"),
+            Some(Scene::ModeSelect(m)) => m.draw(&mut draw, "Mode"),
+            Some(Scene::PuzzleSelect(m)) => m.draw(&mut draw, "Puzzles"),
+            Some(Scene::Options(o)) => {
+                if game.is_some() {
+                    dim(&mut draw);
+                }
+                o.draw(&mut draw, &settings);
+            }
+            Some(Scene::Paused(m)) => {
+                dim(&mut draw);
+                m.draw(&mut draw, "Paused");
+            }
+            Some(Scene::Results(m)) => {
+                dim(&mut draw);
+                let title = game.as_ref().map_or_else(String::new, Game::results_title);
+                m.draw(&mut draw, &title);
+                if let Some(game) = &game {
+                    draw_panel(&mut draw, &game.results().summary());
+                }
+                if let Some(game) = game.as_ref().filter(|g| g.finesse.pieces > 0) {
+                    draw_caption(&mut draw, &game.finesse.summary());
+                }
+            }
+            Some(Scene::Playing) | None => {}
+        }
+        if let Some(notice) = &notice {
+            draw_caption(&mut draw, notice);
+        }
+    }
+}
+
+/// The game to play in `mode`, along with the puzzle being played in puzzle mode.
+fn setup(
+    mode: GameMode,
+    width: i32,
+    height: i32,
+    ruleset: &Ruleset,
+    seed: i32,
+) -> Result<(Tetris<DummyRng>, Option<PuzzleRun<DummyRng>>), String> {
+    let rng = DummyRng::with_seed(seed);
+    if let GameMode::Puzzle(i) = mode {
+        let pack = puzzles::pack();
+        let puzzle = pack
+            .get(i)
+            .ok_or_else(|| format!("there is no puzzle {i}"))?;
+        let (tetris, run) = puzzle
+            .start(rng)
+            .map_err(|e| format!("{}: {e}", puzzle.name))?;
+        return Ok((tetris, Some(run)));
+    }
+    let pieces = mode.piece_set();
+    let tetris = Tetris::with_ruleset(width, height, ruleset.clone(), pieces, rng)
+        .map_err(|e| e.to_string())?;
+    Ok((tetris, None))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_game(
+    layout: &BoardLayout,
+    board: &impl IterateDimensions<Output = Option<Cell>>,
+    falling: Option<Falling>,
+    queue: &[impl IterateDimensions<Output = Option<Cell>> + Clone],
+    held: Option<impl IterateDimensions<Output = Option<Cell>>>,
+    readouts: &[(&str, String)],
+    draw: &mut RaylibDrawHandle,
+    skin: &Skin,
+    effects: Option<&Effects>,
+    alpha: f32,
+) {
+    let cell_size = layout.cell_size;
+    let (dx, dy) = effects.map_or((0.0, 0.0), Effects::shake_offset);
+    let shaken = layout.playfield.offset((dx * cell_size, dy * cell_size));
+    draw_playfield(&shaken, board, draw, cell_size, skin, effects);
+    if let Some(falling) = falling {
+        // Over the board as it is, not where its rows are drawn while the stack collapses.
+        draw_piece(&shaken, &falling.ghost, 0.0, draw, cell_size, skin);
+        draw_piece(
+            &shaken,
+            &falling.player,
+            falling.lift,
+            draw,
             cell_size,
-            Some(tetris.next()),
-            "Next",
-            &mut draw,
-            &brick_texture,
+            skin,
         );
+    }
+    if let Some(effects) = effects {
+        effects.draw(draw, &shaken, cell_size, board.width(), alpha);
+    }
+
+    draw_boxed(
+        layout.next,
+        cell_size,
+        queue.first().cloned(),
+        "Next",
+        draw,
+        skin,
+    );
 
+    // The rest of the queue goes at half size to the right of the playfield, as much of it
+    // as fits.
+    for (i, item) in queue.iter().skip(1).take(layout.queue_slots).enumerate() {
         draw_boxed(
-            (playfield.x() / 2.0 - cell_size * 2.0, cell_size * 8.0),
-            cell_size,
-            tetris.held(),
-            "Hold",
-            &mut draw,
-            &brick_texture,
+            layout.queue_slot(i),
+            cell_size / 2.0,
+            Some(item.clone()),
+            "",
+            draw,
+            skin,
         );
-
-        draw_score(cell_size, tetris.score(), &mut draw, &playfield);
     }
+
+    draw_boxed(layout.hold, cell_size, held, "Hold", draw, skin);
+
+    draw_readouts(draw, &layout.hud, cell_size, readouts);
 }
 
-fn draw_score(cell_size: f32, score: u32, draw: &mut RaylibDrawHandle, playfield_area: &Area) {
-    let text = format!("{:0>5}", score);
-    let text_w = draw.measure_text(&text, cell_size as i32);
-    draw.draw_text(
-        &text,
-        (playfield_area.x() / 2.0) as i32 - text_w / 2,
-        8,
-        cell_size as i32,
-        Color::WHITE,
-    );
+fn spectate(addr: &str) {
+    let mut client = match SpectatorClient::connect(addr) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Could not connect to {addr}: {e}");
+            return;
+        }
+    };
+
+    let (mut rl, thread) = raylib::init()
+        .size(920, 720)
+        .title("Tetris - Spectating")
+        .resizable()
+        .build();
+
+    let skin = Skin::load(&mut rl, &thread, &Settings::load().skin);
+
+    rl.set_target_fps(60);
+
+    while !rl.window_should_close() {
+        let (width, height) = (rl.get_screen_width(), rl.get_screen_height());
+        let connected = client.connected();
+        let snapshot = client.latest();
+
+        let mut draw = rl.begin_drawing(&thread);
+        draw.clear_background(Color::new(0, 44, 88, 255));
+        skin.draw_background(&mut draw);
+
+        let Some(snapshot) = snapshot else {
+            let text = if connected {
+                "Waiting for game..."
+            } else {
+                "Disconnected"
+            };
+            let text_w = draw.measure_text(text, 32);
+            draw.draw_text(text, width / 2 - text_w / 2, height / 2, 32, Color::WHITE);
+            continue;
+        };
+
+        let layouts = layout::boards(
+            (width as f32, height as f32),
+            &[(snapshot.width(), snapshot.height())],
+        );
+
+        draw_game(
+            &layouts[0],
+            snapshot,
+            None,
+            &snapshot.queue,
+            snapshot.hold.clone(),
+            &[("Score", format!("{:0>5}", snapshot.score))],
+            &mut draw,
+            &skin,
+            None,
+            0.0,
+        );
+
+        if !connected {
+            draw.draw_text("Disconnected", 8, height - 40, 32, Color::RED);
+        }
+    }
 }
 
 fn draw_boxed(
@@ -129,7 +871,7 @@ fn draw_boxed(
     item: Option<impl IterateDimensions<Output = Option<Cell>>>,
     label: &str,
     draw: &mut RaylibDrawHandle,
-    brick_texture: &Texture2D,
+    skin: &Skin,
 ) {
     draw.draw_text(
         label,
@@ -142,111 +884,155 @@ fn draw_boxed(
 
     if let Some(item) = item {
         item.iter_dim(|x, y, c| {
-            if let Some(c) = c {
-                match c {
-                    Cell::Normal(TetrisColor(r, g, b)) => {
-                        draw_rect(
-                            draw,
-                            (
-                                (x as f32 * cell_size + ox).ceil(),
-                                (y as f32 * cell_size + oy).ceil(),
-                                cell_size.ceil(),
-                                cell_size.ceil(),
-                            ),
-                            Color::new(r, g, b, 255),
-                            brick_texture,
-                        );
-                    }
-                    Cell::Ghost => todo!(),
-                }
-            } else {
-                draw_rect(
-                    draw,
-                    (
-                        (x as f32 * cell_size + ox).ceil(),
-                        (y as f32 * cell_size + oy).ceil(),
-                        cell_size.ceil(),
-                        cell_size.ceil(),
-                    ),
-                    Color::BLACK,
-                    brick_texture,
-                );
+            let rect = (
+                (x as f32 * cell_size + ox).ceil(),
+                (y as f32 * cell_size + oy).ceil(),
+                cell_size.ceil(),
+                cell_size.ceil(),
+            );
+            match c {
+                Some(c) => skin.draw_cell(draw, rect, c),
+                None => skin.draw_empty(draw, rect),
             }
         });
     } else {
-        draw_rect(
+        skin.draw_empty(
             draw,
             (ox, oy, (cell_size * 4.0).ceil(), (cell_size * 4.0).ceil()),
-            Color::BLACK,
-            brick_texture,
         );
     }
 }
 
-fn handle_events(
+/// Records and applies `actions`, returning the ones that did something.
+fn handle_actions(
     tetris: &mut Tetris<DummyRng>,
-    rl: &mut RaylibHandle,
-    rotate_sound: &Sound,
-    wrong_sound: &Sound,
+    replay: &mut Replay,
+    actions: &[Action],
+    audio: &AudioBox,
+) -> Vec<Action> {
+    let mut applied = vec![];
+    for &action in actions {
+        replay.record(tetris.frame(), action);
+        let done = tetris.apply(action);
+        if done {
+            applied.push(action);
+        }
+        let sound = match (action, done) {
+            (Action::SoftDrop | Action::HardDrop, _) => None,
+            (Action::ShiftLeft | Action::ShiftRight, false) => None,
+            (_, false) => Some("wrong"),
+            (Action::RotateLeft | Action::RotateRight, true) => Some("rotate"),
+            (Action::Hold, true) => Some("hold"),
+            (_, true) => Some("move"),
+        };
+        if let Some(sound) = sound {
+            audio.play_sound(sound);
+        }
+    }
+    applied
+}
+
+/// The trainer's panel under the hold box: faults so far and the best inputs for the last piece.
+fn draw_finesse(layout: &BoardLayout, finesse: &Finesse, draw: &mut RaylibDrawHandle) {
+    let x = layout.next.0 as i32;
+    let size = (layout.cell_size * 0.6) as i32;
+    let mut y = layout.hud.y() as i32;
+    draw.draw_text(
+        &format!("Faults: {}", finesse.faults),
+        x,
+        y,
+        size,
+        Color::WHITE,
+    );
+
+    if let Some(suggestion) = &finesse.suggestion {
+        let inputs: Vec<&str> = suggestion
+            .iter()
+            .map(|&a| finesse::input_label(a))
+            .collect();
+        y += size * 3 / 2;
+        draw.draw_text(
+            &format!("Try: {}", inputs.join(" ")),
+            x,
+            y,
+            size,
+            Color::SKYBLUE,
+        );
+    }
+
+    let Some(last) = &finesse.last else {
+        return;
+    };
+    let color = if last.faults() > 0 {
+        Color::RED
+    } else {
+        Color::GREEN
+    };
+    let best: Vec<&str> = last
+        .optimal
+        .iter()
+        .map(|&a| finesse::input_label(a))
+        .collect();
+    for line in [
+        format!("Pressed: {}", last.presses),
+        format!("Best: {}", best.join(" ")),
+    ] {
+        y += size * 3 / 2;
+        draw.draw_text(&line, x, y, size, color);
+    }
+}
+
+/// The opener being built drawn see-through where pieces still have to go, with cells
+/// placed off the plan marked in red.
+fn draw_guide(
+    layout: &BoardLayout,
+    guide: &Guide,
+    tetris: &Tetris<DummyRng>,
+    draw: &mut RaylibDrawHandle,
+    skin: &Skin,
 ) {
-    let pressed_key = rl.get_key_pressed();
-    if let Some(key) = pressed_key {
-        match key {
-            KeyboardKey::KEY_LEFT => {
-                if tetris.move_left() {
-                } else {
-                    wrong_sound.play();
-                }
-            }
-            KeyboardKey::KEY_RIGHT => {
-                if tetris.move_right() {
-                } else {
-                    wrong_sound.play();
-                }
-            }
-            KeyboardKey::KEY_DOWN => {
-                tetris.move_down();
-            }
-            KeyboardKey::KEY_A => {
-                if tetris.rotate_left() {
-                    rotate_sound.play();
-                } else {
-                    wrong_sound.play();
-                }
-            }
-            KeyboardKey::KEY_D => {
-                if tetris.rotate_right() {
-                    rotate_sound.play();
-                } else {
-                    wrong_sound.play();
-                }
-            }
-            KeyboardKey::KEY_SPACE => {
-                tetris.drop_block();
-            }
-            KeyboardKey::KEY_S => {
-                tetris.swap_held();
-            }
-            KeyboardKey::KEY_R => {
-                let t = Tetris::new(
-                    tetris.width(),
-                    tetris.height(),
-                    DummyRng::with_seed(tetris.score() as i32),
-                );
-                *tetris = t;
-            }
-            _ => {}
+    let Some(opener) = guide.current() else {
+        return;
+    };
+    let board = tetris.board();
+    let (playfield, cell_size) = (layout.playfield, layout.cell_size);
+    let rect = |(x, y): (i32, i32)| {
+        (
+            (x as f32 * cell_size + playfield.x()).ceil(),
+            (y as f32 * cell_size + playfield.y()).ceil(),
+            cell_size.ceil(),
+            cell_size.ceil(),
+        )
+    };
+    for (name, cells) in opener.targets(board) {
+        let Some(piece) = tetris.pieces().pieces().iter().find(|p| p.name == name) else {
+            continue;
+        };
+        for &cell in cells.iter().filter(|&&c| !board.is_taken(c)) {
+            skin.draw_faded(draw, rect(cell), piece.brick.cell(), 0.35);
         }
     }
+    for cell in opener.deviations(board) {
+        let (x, y, w, h) = rect(cell);
+        draw.draw_rectangle(
+            x as i32,
+            y as i32,
+            w as i32,
+            h as i32,
+            Color::new(255, 0, 0, 120),
+        );
+    }
 }
 
 fn draw_playfield(
     playfield_area: &Area,
-    tetris: &Tetris<DummyRng>,
+    tetris: &impl IterateDimensions<Output = Option<Cell>>,
     draw: &mut RaylibDrawHandle,
     cell_size: f32,
-    brick_texture: &Texture2D,
+    skin: &Skin,
+    effects: Option<&Effects>,
 ) {
+    let height = tetris.height();
     tetris.iter_dim(|x, y, c| {
         stroke_rect(
             draw,
@@ -259,66 +1045,81 @@ fn draw_playfield(
             Color::new(255, 255, 255, 100),
         );
         if let Some(c) = c {
-            match c {
-                tetris_core::cell::Cell::Normal(TetrisColor(r, g, b)) => {
-                    draw_rect(
-                        draw,
-                        (
-                            (x as f32 * cell_size + playfield_area.x()).ceil(),
-                            (y as f32 * cell_size + playfield_area.y()).ceil(),
-                            cell_size.ceil(),
-                            cell_size.ceil(),
-                        ),
-                        Color::new(r, g, b, 255),
-                        brick_texture,
-                    );
-                }
-                tetris_core::cell::Cell::Ghost => {
-                    draw_rect(
-                        draw,
-                        (
-                            (x as f32 * cell_size + playfield_area.x()).ceil(),
-                            (y as f32 * cell_size + playfield_area.y()).ceil(),
-                            cell_size.ceil(),
-                            cell_size.ceil(),
-                        ),
-                        Color::new(255, 255, 255, 127),
-                        brick_texture,
-                    );
-                }
-            }
+            let y = y as f32 - effects.map_or(0.0, |e| e.row_offset(y, height));
+            skin.draw_cell(
+                draw,
+                (
+                    (x as f32 * cell_size + playfield_area.x()).ceil(),
+                    (y * cell_size + playfield_area.y()).ceil(),
+                    cell_size.ceil(),
+                    cell_size.ceil(),
+                ),
+                c,
+            );
         }
     });
 }
 
-fn draw_rect(
+/// The piece in play and its ghost, drawn over the board.
+struct Falling {
+    player: Player,
+    ghost: Player,
+    /// Rows above its position the piece is drawn at.
+    lift: f32,
+}
+
+/// The piece in play of `tetris`, none between pieces. A piece that fell since `before`, the
+/// piece a step ago, is drawn `alpha` of the way from there, as far as the renderer is
+/// between that step and the next.
+fn falling<R: Randomizer>(
+    tetris: &Tetris<R>,
+    before: Option<Player>,
+    alpha: f32,
+) -> Option<Falling> {
+    let player = tetris.player();
+    let (x, y) = player.position();
+    let lift = before
+        .filter(|b| {
+            b.brick().kind() == player.brick().kind()
+                && b.rotation() == player.rotation()
+                && b.position().0 == x
+                && b.position().1 < y
+        })
+        .map_or(0.0, |b| (y - b.position().1) as f32 * (1.0 - alpha));
+    (!tetris.spawning()).then(|| Falling {
+        player,
+        ghost: tetris.ghost(),
+        lift,
+    })
+}
+
+fn draw_piece(
+    playfield_area: &Area,
+    player: &Player,
+    lift: f32,
     draw: &mut RaylibDrawHandle,
-    (x, y, w, h): (f32, f32, f32, f32),
-    color: Color,
-    brick_texture: &Texture2D,
+    cell_size: f32,
+    skin: &Skin,
 ) {
-    draw.draw_texture_pro(
-        brick_texture,
-        Rectangle::new(
-            0.0,
-            0.0,
-            brick_texture.width as f32,
-            brick_texture.height as f32,
-        ),
-        Rectangle::new(x, y, w, h),
-        Vector2::zero(),
-        0.0,
-        color,
-    );
+    let (px, py) = player.position();
+    player.brick().iter_dim(|x, y, c| {
+        let row = (py + y) as f32 - lift;
+        // Cells in the buffer rows above the playfield are not drawn.
+        if let Some(c) = c.filter(|_| row >= 0.0) {
+            skin.draw_cell(
+                draw,
+                (
+                    ((px + x) as f32 * cell_size + playfield_area.x()).ceil(),
+                    (row * cell_size + playfield_area.y()).ceil(),
+                    cell_size.ceil(),
+                    cell_size.ceil(),
+                ),
+                c,
+            );
+        }
+    });
 }
 
 fn stroke_rect(draw: &mut RaylibDrawHandle, (x, y, w, h): (f32, f32, f32, f32), color: Color) {
     draw.draw_rectangle_lines(x as i32, y as i32, w as i32, h as i32, color);
 }
-
-fn resize_playfield((w, h): (i32, i32), tetris_w: i32, tetris_h: i32, pf: &mut Area) -> f32 {
-    let cell_size = h as f32 / tetris_h as f32;
-    let pf_width = cell_size * tetris_w as f32;
-    *pf = Area::new(w as f32 / 2.0 - pf_width / 2.0, 0.0, cell_size, cell_size);
-    cell_size
-}
@@ -0,0 +1,217 @@
+use tetris_core::{opener::Opener, tetris::Tetris, traits::Randomizer};
+
+/// The openers shipped with the game.
+const PACK: &[&str] = &[
+    include_str!("../openers/tki.toml"),
+    include_str!("../openers/mko.toml"),
+    include_str!("../openers/dt-cannon.toml"),
+    include_str!("../openers/pco.toml"),
+];
+
+pub fn pack() -> Vec<Opener> {
+    PACK.iter()
+        .map(|text| toml::from_str(text).expect("shipped openers are valid TOML"))
+        .collect()
+}
+
+/// Suggests an opener in practice mode and follows the player building it.
+#[derive(Debug, Clone)]
+pub struct Guide {
+    openers: Vec<Opener>,
+    /// Openers the pieces seen so far can still build from the board.
+    fitting: Vec<usize>,
+    /// The opener shown, kept when the player strays from it so the mistake can be shown.
+    current: Option<usize>,
+    /// The bag of the opener shown being placed, unset once it is built.
+    bag: Option<usize>,
+}
+
+impl Default for Guide {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Guide {
+    pub fn new() -> Self {
+        Self {
+            openers: pack(),
+            fitting: vec![],
+            current: None,
+            bag: None,
+        }
+    }
+
+    /// Call whenever a piece was placed or taken back. Sticks with the opener shown while
+    /// it can still be built, otherwise switches to the first one that can.
+    pub fn update<R: Randomizer>(&mut self, tetris: &Tetris<R>) {
+        let board = tetris.board();
+        self.fitting = (0..self.openers.len())
+            .filter(|&i| {
+                let opener = &self.openers[i];
+                opener.feasible(tetris)
+                    || (opener.is_built(board) && opener.deviations(board).is_empty())
+            })
+            .collect();
+        if self.current.is_none_or(|i| !self.fitting.contains(&i)) {
+            self.current = self.fitting.first().copied().or(self.current);
+        }
+        self.bag = self.current.and_then(|i| self.openers[i].bag(board));
+    }
+
+    pub fn current(&self) -> Option<&Opener> {
+        self.current.map(|i| &self.openers[i])
+    }
+
+    /// One line naming the opener shown and the others that would still work.
+    pub fn caption(&self) -> String {
+        let Some(index) = self.current else {
+            return "No opener fits these pieces".to_owned();
+        };
+        let current = &self.openers[index];
+        let name = match self.bag {
+            Some(bag) if current.bags.len() > 1 => format!("{}, bag {}", current.name, bag + 1),
+            _ => current.name.clone(),
+        };
+        let others: Vec<&str> = self
+            .fitting
+            .iter()
+            .filter(|&&i| i != index)
+            .map(|&i| self.openers[i].name.as_str())
+            .collect();
+        if !self.fitting.contains(&index) {
+            format!("{name} - off the plan")
+        } else if others.is_empty() {
+            name
+        } else {
+            format!("{name} (also: {})", others.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetris_core::{
+        board::Board,
+        cell::Cell,
+        piece_set::PieceSet,
+        ruleset::{HoldRule, Ruleset},
+    };
+
+    use super::*;
+    use crate::DummyRng;
+
+    fn game(order: &str, hold: HoldRule) -> Tetris<DummyRng> {
+        game_on(&[], order, hold)
+    }
+
+    /// A game with the pieces in `order` coming up, on a board with `cells` already filled.
+    fn game_on(cells: &[(i32, i32)], order: &str, hold: HoldRule) -> Tetris<DummyRng> {
+        let pieces = PieceSet::tetrominoes();
+        let queue = order
+            .chars()
+            .map(|c| {
+                let piece = pieces.pieces().iter().find(|p| p.name == c.to_string());
+                piece.unwrap().brick
+            })
+            .collect();
+        let ruleset = Ruleset {
+            hold,
+            ..Ruleset::guideline()
+        };
+        let mut board = Board::with_buffer(10, 20, ruleset.buffer_rows as i32);
+        for &cell in cells {
+            board.set_field(cell, Some(Cell::Garbage));
+        }
+        Tetris::with_setup(board, queue, ruleset, pieces, DummyRng::default()).unwrap()
+    }
+
+    /// Every order of the pieces in `pieces`.
+    fn orders(pieces: &str) -> Vec<String> {
+        if pieces.len() <= 1 {
+            return vec![pieces.to_owned()];
+        }
+        let mut all = vec![];
+        for (i, first) in pieces.char_indices() {
+            let rest = format!("{}{}", &pieces[..i], &pieces[i + 1..]);
+            all.extend(orders(&rest).into_iter().map(|o| format!("{first}{o}")));
+        }
+        all
+    }
+
+    #[test]
+    fn every_opener_is_buildable() {
+        for opener in pack() {
+            // Some order of each bag, after the ones found for the bags before it.
+            let mut sequence = String::new();
+            for bag in 0..opener.bags.len() {
+                let order = orders("IOTJLSZ").into_iter().find(|order| {
+                    let tetris = game(&format!("{sequence}{order}"), HoldRule::OncePerPiece);
+                    opener.feasible(&tetris)
+                });
+                let Some(order) = order else {
+                    panic!("bag {} of {} cannot be built", bag + 1, opener.name);
+                };
+                sequence += &order;
+            }
+        }
+    }
+
+    /// Names of the openers the guide still suggests for the pieces in `order`.
+    fn fitting(order: &str, hold: HoldRule) -> Vec<String> {
+        fitting_on(&[], order, hold)
+    }
+
+    fn fitting_on(cells: &[(i32, i32)], order: &str, hold: HoldRule) -> Vec<String> {
+        let mut guide = Guide::new();
+        guide.update(&game_on(cells, order, hold));
+        let names = guide.fitting.iter().map(|&i| guide.openers[i].name.clone());
+        names.collect()
+    }
+
+    #[test]
+    fn openers_are_filtered_by_bag() {
+        // The DT cannon's T and O both rest on the L, which comes last.
+        assert_eq!(
+            fitting("ITSZOLJ", HoldRule::OncePerPiece),
+            ["TKI", "MKO", "PCO"]
+        );
+        // The PCO has no T, without hold it has to go down somewhere off the shape.
+        assert_eq!(fitting("ITSZOLJ", HoldRule::Disabled), ["TKI"]);
+        // The MKO's L and J rest on pieces still to come, one too many to hold.
+        assert_eq!(
+            fitting("LJITOSZ", HoldRule::OncePerPiece),
+            ["TKI", "DT cannon"]
+        );
+        // The T comes last, too late for the TKI, whose O rests on it.
+        assert_eq!(fitting("ISZOLJT", HoldRule::Disabled), ["PCO"]);
+        // The I comes last, too late for all of them, built on it.
+        assert!(fitting("SZOLJTI", HoldRule::Disabled).is_empty());
+    }
+
+    /// The cells of the first bag of the opener named `name`.
+    fn first_bag(name: &str) -> Vec<(i32, i32)> {
+        let opener = pack().into_iter().find(|o| o.name == name).unwrap();
+        let first = Opener {
+            bags: opener.bags[..1].to_vec(),
+            ..opener
+        };
+        let targets = first.targets(&Board::with_buffer(10, 20, 0));
+        targets.into_iter().flat_map(|(_, cells)| cells).collect()
+    }
+
+    #[test]
+    fn two_bag_openers_are_filtered_by_their_second_bag() {
+        for (name, order) in [("MKO", "IJOLSZT"), ("DT cannon", "IOJLSZT")] {
+            let cells = first_bag(name);
+            // Only the opener whose first bag is down, its T kept for the spin.
+            assert_eq!(fitting_on(&cells, order, HoldRule::Disabled), [name]);
+            // The S rests on other pieces of the second bag.
+            assert!(fitting_on(&cells, "SZOLJTI", HoldRule::Disabled).is_empty());
+
+            let mut guide = Guide::new();
+            guide.update(&game_on(&cells, order, HoldRule::Disabled));
+            assert_eq!(guide.caption(), format!("{name}, bag 2"));
+        }
+    }
+}
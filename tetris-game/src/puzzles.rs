@@ -0,0 +1,16 @@
+use tetris_core::puzzle::Puzzle;
+
+/// The puzzles shipped with the game, in the order they are offered.
+const PACK: &[&str] = &[
+    include_str!("../puzzles/four-at-once.toml"),
+    include_str!("../puzzles/hook.toml"),
+    include_str!("../puzzles/two-steps.toml"),
+    include_str!("../puzzles/t-spin-double.toml"),
+    include_str!("../puzzles/save-it-for-later.toml"),
+];
+
+pub fn pack() -> Vec<Puzzle> {
+    PACK.iter()
+        .map(|text| toml::from_str(text).expect("shipped puzzles are valid TOML"))
+        .collect()
+}
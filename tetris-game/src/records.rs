@@ -0,0 +1,100 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use tetris_core::{
+    stats::{self, Stats},
+    tetris::Tetris,
+    traits::Randomizer,
+};
+
+use crate::{scene::GameMode, settings};
+
+/// A finished game as kept in the history file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    /// Seconds since the Unix epoch when the game ended.
+    pub finished: u64,
+    pub mode: GameMode,
+    pub ruleset: String,
+    pub score: u32,
+    pub lines: u32,
+    pub level: u32,
+    /// Garbage rows sent to the other board, in versus.
+    pub attack: Option<u32>,
+    pub stats: Stats,
+}
+
+impl Record {
+    pub fn new<R: Randomizer>(mode: GameMode, tetris: &Tetris<R>, attack: Option<u32>) -> Self {
+        let finished = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self {
+            finished,
+            mode,
+            ruleset: tetris.ruleset().name.clone(),
+            score: tetris.score(),
+            lines: tetris.lines(),
+            level: tetris.level(),
+            attack,
+            stats: tetris.stats().clone(),
+        }
+    }
+
+    /// Attack per minute, in versus.
+    pub fn apm(&self) -> Option<f32> {
+        let minutes = self.stats.seconds() / 60.0;
+        self.attack.map(|attack| stats::per(attack as f32, minutes))
+    }
+
+    /// What the results screen shows, one line each.
+    pub fn summary(&self) -> Vec<String> {
+        let stats = &self.stats;
+        let seconds = stats.seconds();
+        let [singles, doubles, triples, tetrises] = stats.clears;
+        let [_, tss, tsd, tst] = stats.t_spins;
+        let mut lines = vec![
+            format!("Time {}:{:04.1}", (seconds / 60.0) as u32, seconds % 60.0),
+            format!("Score {}  Level {}", self.score, self.level),
+            format!("Pieces {}  ({:.2} PPS)", stats.pieces, stats.pps()),
+            format!("Keys {}  ({:.2} KPP)", stats.keys, stats.kpp()),
+            format!("Lines {}", self.lines),
+            format!("Singles {singles}  Doubles {doubles}  Triples {triples}  Tetrises {tetrises}"),
+            format!(
+                "T-spins {}  (single {tss}, double {tsd}, triple {tst})",
+                stats.t_spins.iter().sum::<u32>()
+            ),
+            format!(
+                "Max combo {}  Max back-to-back {}",
+                stats.max_combo, stats.max_back_to_back
+            ),
+            format!("Holds {}", stats.holds),
+        ];
+        if let (Some(attack), Some(apm)) = (self.attack, self.apm()) {
+            lines.push(format!("Attack {attack}  ({apm:.1} APM)"));
+        }
+        lines
+    }
+
+    fn path() -> Option<PathBuf> {
+        Some(settings::config_dir()?.join("history.jsonl"))
+    }
+
+    /// Adds the game to the end of the history file, one JSON object per line.
+    pub fn append(&self) -> io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let line = serde_json::to_string(self).map_err(io::Error::other)?;
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{line}")
+    }
+}
@@ -0,0 +1,31 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+use tetris_core::prelude::{Replay, Ruleset};
+
+use crate::scene::GameMode;
+
+/// A recorded game with everything needed to play it back the same way.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayFile {
+    pub mode: GameMode,
+    pub width: i32,
+    pub height: i32,
+    pub ruleset: Ruleset,
+    /// Pace of the bot, which has to match for a versus game to play out the same.
+    #[serde(default)]
+    pub bot_delay: u32,
+    pub replay: Replay,
+}
+
+impl ReplayFile {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        serde_json::from_str(&text).map_err(io::Error::other)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let text = serde_json::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, text)
+    }
+}
@@ -0,0 +1,374 @@
+use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
+use tetris_core::prelude::PieceSet;
+
+use crate::{input::MenuInput, puzzles, settings::Settings, skin};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+    Marathon,
+    /// Marathon with the eighteen pentominoes.
+    Pentris,
+    /// Marathon with every piece twice as large.
+    Big,
+    /// Marathon showing the fewest inputs for every piece placed.
+    Finesse,
+    /// Marathon where placements can be taken back and placed again.
+    Practice,
+    /// The bot plays marathon on its own.
+    Bot,
+    /// Side by side against the bot, clearing lines sends garbage to the other board.
+    Versus,
+    /// The puzzle at this index of the shipped pack.
+    Puzzle(usize),
+}
+
+impl GameMode {
+    pub const ALL: &'static [GameMode] = &[
+        GameMode::Marathon,
+        GameMode::Pentris,
+        GameMode::Big,
+        GameMode::Finesse,
+        GameMode::Practice,
+        GameMode::Bot,
+        GameMode::Versus,
+        GameMode::Puzzle(0),
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Marathon => "Marathon",
+            GameMode::Pentris => "Pentris",
+            GameMode::Big => "Big",
+            GameMode::Finesse => "Finesse trainer",
+            GameMode::Practice => "Practice",
+            GameMode::Bot => "Watch the bot",
+            GameMode::Versus => "Versus CPU",
+            GameMode::Puzzle(_) => "Puzzles",
+        }
+    }
+
+    /// How many times wider and higher than chosen the board is. Big pieces get a board
+    /// large enough to hold as many of them as a normal one holds normal pieces.
+    pub fn board_scale(&self) -> i32 {
+        match self {
+            GameMode::Big => 2,
+            _ => 1,
+        }
+    }
+
+    /// Whether placed pieces can be taken back, which a recorded replay cannot play back.
+    pub fn undoable(&self) -> bool {
+        matches!(self, GameMode::Practice | GameMode::Puzzle(_))
+    }
+
+    pub fn piece_set(&self) -> PieceSet {
+        match self {
+            GameMode::Marathon
+            | GameMode::Finesse
+            | GameMode::Practice
+            | GameMode::Bot
+            | GameMode::Versus
+            | GameMode::Puzzle(_) => PieceSet::tetrominoes(),
+            GameMode::Pentris => PieceSet::pentominoes(),
+            GameMode::Big => PieceSet::tetrominoes().scaled(2),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Menu {
+    items: Vec<String>,
+    selected: usize,
+}
+
+impl Menu {
+    pub fn new(items: &[&str]) -> Self {
+        Self::with_items(items.iter().map(|s| s.to_string()).collect())
+    }
+
+    pub fn with_items(items: Vec<String>) -> Self {
+        Self { items, selected: 0 }
+    }
+
+    /// Moves the cursor, returning the selected index when it is confirmed.
+    pub fn navigate(&mut self, input: MenuInput) -> Option<usize> {
+        let len = self.items.len();
+        match input {
+            MenuInput::Up => self.selected = (self.selected + len - 1) % len,
+            MenuInput::Down => self.selected = (self.selected + 1) % len,
+            MenuInput::Confirm => return Some(self.selected),
+            _ => {}
+        }
+        None
+    }
+
+    pub fn draw(&self, draw: &mut RaylibDrawHandle, title: &str) {
+        draw_menu(draw, title, &self.items, self.selected);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OptionRow {
+    Das,
+    Arr,
+    Volume,
+    SfxVolume,
+    MusicVolume,
+    UiVolume,
+    Width,
+    Height,
+    Skin,
+    BotDelay,
+    LineClearFrames,
+    ClearAnimation,
+    LockFlash,
+    HardDropTrail,
+    Particles,
+    ScreenShake,
+    Callouts,
+    Back,
+}
+
+impl OptionRow {
+    const ALL: [OptionRow; 18] = [
+        OptionRow::Das,
+        OptionRow::Arr,
+        OptionRow::Volume,
+        OptionRow::SfxVolume,
+        OptionRow::MusicVolume,
+        OptionRow::UiVolume,
+        OptionRow::Width,
+        OptionRow::Height,
+        OptionRow::Skin,
+        OptionRow::BotDelay,
+        OptionRow::LineClearFrames,
+        OptionRow::ClearAnimation,
+        OptionRow::LockFlash,
+        OptionRow::HardDropTrail,
+        OptionRow::Particles,
+        OptionRow::ScreenShake,
+        OptionRow::Callouts,
+        OptionRow::Back,
+    ];
+
+    fn label(&self, settings: &Settings) -> String {
+        let on_off = |v: bool| if v { "on" } else { "off" };
+        let effects = &settings.effects;
+        match self {
+            OptionRow::Das => format!("DAS  < {} frames >", settings.das),
+            OptionRow::Arr => format!("ARR  < {} frames >", settings.arr),
+            OptionRow::Volume => format!("Volume  < {:.0}% >", settings.volume * 100.0),
+            OptionRow::SfxVolume => {
+                format!("Sound effects  < {:.0}% >", settings.sfx_volume * 100.0)
+            }
+            OptionRow::MusicVolume => format!("Music  < {:.0}% >", settings.music_volume * 100.0),
+            OptionRow::UiVolume => format!("Menu sounds  < {:.0}% >", settings.ui_volume * 100.0),
+            OptionRow::Width => format!("Board width  < {} >", settings.width),
+            OptionRow::Height => format!("Board height  < {} >", settings.height),
+            OptionRow::Skin => format!("Skin  < {} >", settings.skin),
+            OptionRow::BotDelay => format!("Bot delay  < {} frames >", settings.bot_delay),
+            OptionRow::LineClearFrames => {
+                format!("Line clear  < {} frames >", effects.line_clear_frames)
+            }
+            OptionRow::ClearAnimation => {
+                format!("Clear animation  < {} >", effects.clear_animation.name())
+            }
+            OptionRow::LockFlash => format!("Lock flash  < {} >", on_off(effects.lock_flash)),
+            OptionRow::HardDropTrail => {
+                format!("Hard drop trail  < {} >", on_off(effects.hard_drop_trail))
+            }
+            OptionRow::Particles => format!("Particles  < {} >", on_off(effects.particles)),
+            OptionRow::ScreenShake => {
+                format!("Screen shake  < {} >", on_off(effects.screen_shake))
+            }
+            OptionRow::Callouts => format!("Callouts  < {} >", on_off(settings.hud.callouts)),
+            OptionRow::Back => "Back".to_owned(),
+        }
+    }
+
+    fn adjust(&self, settings: &mut Settings, delta: i32) {
+        let step = |v: u32, min: u32, max: u32| v.saturating_add_signed(delta).clamp(min, max);
+        let percent = |v: f32| (v + delta as f32 * 0.1).clamp(0.0, 1.0);
+        match self {
+            OptionRow::Das => settings.das = step(settings.das, 0, 30),
+            OptionRow::Arr => settings.arr = step(settings.arr, 0, 10),
+            OptionRow::Volume => settings.volume = percent(settings.volume),
+            OptionRow::SfxVolume => settings.sfx_volume = percent(settings.sfx_volume),
+            OptionRow::MusicVolume => settings.music_volume = percent(settings.music_volume),
+            OptionRow::UiVolume => settings.ui_volume = percent(settings.ui_volume),
+            OptionRow::Width => settings.width = step(settings.width, 4, 40),
+            OptionRow::Height => settings.height = step(settings.height, 8, 40),
+            OptionRow::Skin => {
+                let skins = skin::available();
+                let current = skins.iter().position(|s| *s == settings.skin).unwrap_or(0) as i32;
+                let next = (current + delta).rem_euclid(skins.len() as i32);
+                settings.skin = skins[next as usize].clone();
+            }
+            OptionRow::BotDelay => settings.bot_delay = step(settings.bot_delay, 0, 30),
+            OptionRow::LineClearFrames => {
+                settings.effects.line_clear_frames = step(settings.effects.line_clear_frames, 1, 60)
+            }
+            OptionRow::ClearAnimation => {
+                settings.effects.clear_animation = settings.effects.clear_animation.cycle(delta)
+            }
+            OptionRow::LockFlash => settings.effects.lock_flash ^= true,
+            OptionRow::HardDropTrail => settings.effects.hard_drop_trail ^= true,
+            OptionRow::Particles => settings.effects.particles ^= true,
+            OptionRow::ScreenShake => settings.effects.screen_shake ^= true,
+            OptionRow::Callouts => settings.hud.callouts ^= true,
+            OptionRow::Back => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OptionsMenu {
+    selected: usize,
+}
+
+impl OptionsMenu {
+    /// Applies the input to the settings, returns true when the menu should close.
+    pub fn navigate(&mut self, input: MenuInput, settings: &mut Settings) -> bool {
+        let len = OptionRow::ALL.len();
+        let row = OptionRow::ALL[self.selected];
+        match input {
+            MenuInput::Up => self.selected = (self.selected + len - 1) % len,
+            MenuInput::Down => self.selected = (self.selected + 1) % len,
+            MenuInput::Left => row.adjust(settings, -1),
+            MenuInput::Right => row.adjust(settings, 1),
+            MenuInput::Confirm => return row == OptionRow::Back,
+            MenuInput::Back => return true,
+        }
+        false
+    }
+
+    pub fn draw(&self, draw: &mut RaylibDrawHandle, settings: &Settings) {
+        let lines: Vec<String> = OptionRow::ALL.iter().map(|r| r.label(settings)).collect();
+        draw_menu(draw, "Options", &lines, self.selected);
+    }
+}
+
+/// What the main loop should do with the scene stack after a frame.
+#[derive(Debug, Clone)]
+pub enum Transition {
+    None,
+    Push(Scene),
+    Pop,
+    Replace(Scene),
+    /// Start a fresh game on top of the title screen.
+    Start(GameMode),
+    Title,
+    Quit,
+}
+
+#[derive(Debug, Clone)]
+pub enum Scene {
+    Title(Menu),
+    ModeSelect(Menu),
+    PuzzleSelect(Menu),
+    Options(OptionsMenu),
+    Playing,
+    Paused(Menu),
+    Results(Menu),
+}
+
+impl Scene {
+    pub fn title() -> Self {
+        Scene::Title(Menu::new(&["Play", "Options", "Quit"]))
+    }
+
+    pub fn mode_select() -> Self {
+        let modes: Vec<&'static str> = GameMode::ALL.iter().map(|m| m.name()).collect();
+        Scene::ModeSelect(Menu::new(&modes))
+    }
+
+    pub fn puzzle_select() -> Self {
+        let items = puzzles::pack()
+            .iter()
+            .map(|p| format!("{} - {}", p.name, p.goal))
+            .collect();
+        Scene::PuzzleSelect(Menu::with_items(items))
+    }
+
+    pub fn paused() -> Self {
+        Scene::Paused(Menu::new(&[
+            "Resume",
+            "Restart",
+            "Options",
+            "Quit to title",
+        ]))
+    }
+
+    pub fn results() -> Self {
+        Scene::Results(Menu::new(&["Retry", "Title"]))
+    }
+
+    /// Whether the game should still be drawn underneath this scene.
+    pub fn shows_game(&self) -> bool {
+        matches!(self, Scene::Playing | Scene::Paused(_) | Scene::Results(_))
+    }
+}
+
+pub fn draw_menu(draw: &mut RaylibDrawHandle, title: &str, items: &[String], selected: usize) {
+    let (width, height) = (draw.get_screen_width(), draw.get_screen_height());
+    let size = (height / 16)
+        .min(height * 2 / (items.len() as i32 * 3 + 8))
+        .max(12);
+    let top = height / 2 - (items.len() as i32 * 3 / 2 + 2) * size / 2;
+
+    let title_w = draw.measure_text(title, size * 2);
+    draw.draw_text(
+        title,
+        width / 2 - title_w / 2,
+        top - size * 2,
+        size * 2,
+        Color::WHITE,
+    );
+
+    for (i, item) in items.iter().enumerate() {
+        let (text, color) = if i == selected {
+            (format!("> {item} <"), Color::YELLOW)
+        } else {
+            (item.clone(), Color::LIGHTGRAY)
+        };
+        let text_w = draw.measure_text(&text, size);
+        draw.draw_text(
+            &text,
+            width / 2 - text_w / 2,
+            top + size + i as i32 * size * 3 / 2,
+            size,
+            color,
+        );
+    }
+}
+
+/// A line of text centred near the bottom of the screen, under a menu.
+pub fn draw_caption(draw: &mut RaylibDrawHandle, text: &str) {
+    let (width, height) = (draw.get_screen_width(), draw.get_screen_height());
+    let size = (height / 24).max(12);
+    let text_w = draw.measure_text(text, size);
+    draw.draw_text(
+        text,
+        width / 2 - text_w / 2,
+        height - size * 3,
+        size,
+        Color::LIGHTGRAY,
+    );
+}
+
+/// Lines of text down the top left corner, e.g. the stats of a finished game.
+pub fn draw_panel(draw: &mut RaylibDrawHandle, lines: &[String]) {
+    let size = (draw.get_screen_height() / 36).max(10);
+    for (i, line) in lines.iter().enumerate() {
+        let y = size + i as i32 * size * 3 / 2;
+        draw.draw_text(line, size, y, size, Color::LIGHTGRAY);
+    }
+}
+
+/// Darkens whatever was drawn so far, so a menu can be shown on top of it.
+pub fn dim(draw: &mut RaylibDrawHandle) {
+    let (width, height) = (draw.get_screen_width(), draw.get_screen_height());
+    draw.draw_rectangle(0, 0, width, height, Color::new(0, 0, 0, 170));
+}
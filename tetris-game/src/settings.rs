@@ -0,0 +1,84 @@
+use std::{env, fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{effects::EffectSettings, hud::HudSettings};
+
+/// Options chosen in the options menu, persisted between runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Frames a direction has to be held before it starts repeating.
+    pub das: u32,
+    /// Frames between repeated moves, 0 moves straight to the wall.
+    pub arr: u32,
+    /// Master volume from 0.0 to 1.0.
+    pub volume: f32,
+    pub sfx_volume: f32,
+    pub music_volume: f32,
+    pub ui_volume: f32,
+    /// Directory with sounds overriding the embedded ones, named after the sound they replace.
+    pub sound_pack: Option<PathBuf>,
+    pub width: u32,
+    pub height: u32,
+    /// Built-in skin or directory name under one of the skin directories.
+    pub skin: String,
+    pub effects: EffectSettings,
+    pub hud: HudSettings,
+    /// Frames between the inputs of the bot, in the watch and versus modes.
+    pub bot_delay: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            das: 10,
+            arr: 2,
+            volume: 0.8,
+            sfx_volume: 1.0,
+            music_volume: 0.6,
+            ui_volume: 0.8,
+            sound_pack: None,
+            width: tetris_core::board::WIDTH,
+            height: tetris_core::board::HEIGHT,
+            skin: "classic".to_owned(),
+            effects: EffectSettings::default(),
+            hud: HudSettings::default(),
+            bot_delay: 6,
+        }
+    }
+}
+
+/// Where the game keeps its files between runs.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+    Some(base.join("tetris"))
+}
+
+impl Settings {
+    fn path() -> Option<PathBuf> {
+        Some(config_dir()?.join("settings.toml"))
+    }
+
+    /// Loads the saved settings, falling back to defaults if there are none or they can't be read.
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = toml::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, text)
+    }
+}
@@ -0,0 +1,274 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use raylib::prelude::*;
+use serde::Deserialize;
+use tetris_core::{
+    cell::Cell,
+    prelude::{Color as TetrisColor, Palette},
+};
+
+pub static BRICK_IMAGE: &[u8] = include_bytes!("../brick.png");
+
+/// Skins that only swap the palette of the embedded tile.
+const BUILT_IN: &[(&str, Palette)] = &[
+    ("classic", Palette::GUIDELINE),
+    ("nes", Palette::NES),
+    ("monochrome", Palette::MONOCHROME),
+    ("colorblind", Palette::COLORBLIND),
+];
+
+/// Tile sheet slots after the seven pieces.
+const GHOST_TILE: i32 = 7;
+const GARBAGE_TILE: i32 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GhostStyle {
+    /// The piece tile in the ghost colour.
+    Tile,
+    Outline,
+    Hidden,
+}
+
+/// Contents of `palette.toml` in a skin directory, every field is optional.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PaletteFile {
+    /// Built-in skin whose palette the others fields modify.
+    base: Option<String>,
+    /// Seven `#rrggbb` colours in I, O, T, J, L, S, Z order.
+    pieces: Option<Vec<String>>,
+    ghost: Option<String>,
+    ghost_style: Option<GhostStyle>,
+    ghost_alpha: Option<f32>,
+    garbage: Option<String>,
+}
+
+fn parse_hex(hex: &str) -> Option<TetrisColor> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(TetrisColor(channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn skin_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from("skins")];
+    let data = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+    if let Some(data) = data {
+        dirs.push(data.join("tetris").join("skins"));
+    }
+    dirs
+}
+
+fn find_skin_dir(name: &str) -> Option<PathBuf> {
+    skin_dirs()
+        .into_iter()
+        .map(|dir| dir.join(name))
+        .find(|dir| dir.is_dir())
+}
+
+/// Names of the built-in skins followed by every skin directory found.
+pub fn available() -> Vec<String> {
+    let mut names: Vec<String> = BUILT_IN.iter().map(|(n, _)| n.to_string()).collect();
+    for dir in skin_dirs() {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.path().is_dir() && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// How cells are drawn: the tile art, its colours and the background behind the playfield.
+#[derive(Debug)]
+pub struct Skin {
+    name: String,
+    palette: Palette,
+    tiles: Texture2D,
+    background: Option<Texture2D>,
+    ghost_style: GhostStyle,
+    ghost_alpha: f32,
+}
+
+impl Skin {
+    /// Loads a built-in skin or a skin directory, falling back to the classic skin.
+    ///
+    /// A skin directory may contain `tiles.png`, a row of square tiles (either a single tile
+    /// tinted per piece, or one per piece followed by ghost and garbage tiles), `background.png`
+    /// and `palette.toml`.
+    pub fn load(rl: &mut RaylibHandle, thread: &RaylibThread, name: &str) -> Self {
+        let load_texture = |rl: &mut RaylibHandle, path: &Path| {
+            let image = Image::load_image(&path.to_string_lossy()).ok()?;
+            rl.load_texture_from_image(thread, &image).ok()
+        };
+        let built_in = |name: &str| BUILT_IN.iter().find(|(n, _)| *n == name).map(|(_, p)| *p);
+
+        let dir = find_skin_dir(name);
+        let file: PaletteFile = dir
+            .as_ref()
+            .and_then(|dir| fs::read_to_string(dir.join("palette.toml")).ok())
+            .and_then(|text| match toml::from_str(&text) {
+                Ok(file) => Some(file),
+                Err(e) => {
+                    eprintln!("Invalid palette for skin {name}: {e}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        let mut palette = file
+            .base
+            .as_deref()
+            .and_then(built_in)
+            .or_else(|| built_in(name))
+            .unwrap_or(Palette::GUIDELINE);
+        for (slot, hex) in palette.pieces.iter_mut().zip(file.pieces.iter().flatten()) {
+            if let Some(color) = parse_hex(hex) {
+                *slot = color;
+            }
+        }
+        if let Some(color) = file.ghost.as_deref().and_then(parse_hex) {
+            palette.ghost = color;
+        }
+        if let Some(color) = file.garbage.as_deref().and_then(parse_hex) {
+            palette.garbage = color;
+        }
+
+        let tiles = dir
+            .as_ref()
+            .and_then(|dir| load_texture(rl, &dir.join("tiles.png")))
+            .unwrap_or_else(|| {
+                let image = Image::load_image_from_mem(".png", BRICK_IMAGE).unwrap();
+                rl.load_texture_from_image(thread, &image).unwrap()
+            });
+        let background = dir
+            .as_ref()
+            .and_then(|dir| load_texture(rl, &dir.join("background.png")));
+
+        Self {
+            name: name.to_owned(),
+            palette,
+            tiles,
+            background,
+            ghost_style: file.ghost_style.unwrap_or(GhostStyle::Tile),
+            ghost_alpha: file.ghost_alpha.unwrap_or(0.5).clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn tile_count(&self) -> i32 {
+        (self.tiles.width / self.tiles.height.max(1)).max(1)
+    }
+
+    fn draw_tile(
+        &self,
+        draw: &mut RaylibDrawHandle,
+        (x, y, w, h): (f32, f32, f32, f32),
+        tile: i32,
+        tint: Color,
+    ) {
+        let size = self.tiles.height as f32;
+        let tile = if tile < self.tile_count() { tile } else { 0 };
+        draw.draw_texture_pro(
+            &self.tiles,
+            Rectangle::new(tile as f32 * size, 0.0, size, size),
+            Rectangle::new(x, y, w, h),
+            Vector2::zero(),
+            0.0,
+            tint,
+        );
+    }
+
+    /// Draws an empty slot, e.g. the background of the next and hold boxes.
+    pub fn draw_empty(&self, draw: &mut RaylibDrawHandle, rect: (f32, f32, f32, f32)) {
+        self.draw_tile(draw, rect, 0, Color::BLACK);
+    }
+
+    /// Palette entry and tile of piece `kind`. Sets with more pieces than the palette has
+    /// colours cycle through them, as the core does.
+    fn slot(&self, kind: usize) -> usize {
+        kind % self.palette.pieces.len()
+    }
+
+    pub fn draw_cell(&self, draw: &mut RaylibDrawHandle, rect: (f32, f32, f32, f32), cell: Cell) {
+        let sheet = self.tile_count() > GHOST_TILE;
+        let rgb = |TetrisColor(r, g, b): TetrisColor, a: u8| Color::new(r, g, b, a);
+        match cell {
+            Cell::Normal { kind, .. } => {
+                let slot = self.slot(kind);
+                if sheet {
+                    self.draw_tile(draw, rect, slot as i32, Color::WHITE);
+                } else {
+                    self.draw_tile(draw, rect, 0, rgb(self.palette.pieces[slot], 255));
+                }
+            }
+            Cell::Ghost => {
+                let alpha = (self.ghost_alpha * 255.0) as u8;
+                let color = rgb(self.palette.ghost, alpha);
+                match self.ghost_style {
+                    GhostStyle::Tile if sheet => self.draw_tile(draw, rect, GHOST_TILE, color),
+                    GhostStyle::Tile => self.draw_tile(draw, rect, 0, color),
+                    GhostStyle::Outline => {
+                        let (x, y, w, h) = rect;
+                        draw.draw_rectangle_lines_ex(
+                            Rectangle::new(x + 1.0, y + 1.0, w - 2.0, h - 2.0),
+                            (w / 10.0).max(1.0),
+                            color,
+                        );
+                    }
+                    GhostStyle::Hidden => {}
+                }
+            }
+            Cell::Garbage if self.tile_count() > GARBAGE_TILE => {
+                self.draw_tile(draw, rect, GARBAGE_TILE, Color::WHITE)
+            }
+            Cell::Garbage => self.draw_tile(draw, rect, 0, rgb(self.palette.garbage, 255)),
+        }
+    }
+
+    /// Draws `cell` see-through in its plain colour, for shapes shown as a guide.
+    pub fn draw_faded(
+        &self,
+        draw: &mut RaylibDrawHandle,
+        rect: (f32, f32, f32, f32),
+        cell: Cell,
+        alpha: f32,
+    ) {
+        let TetrisColor(r, g, b) = match cell {
+            Cell::Normal { kind, .. } => self.palette.pieces[self.slot(kind)],
+            Cell::Ghost => self.palette.ghost,
+            Cell::Garbage => self.palette.garbage,
+        };
+        self.draw_tile(draw, rect, 0, Color::new(r, g, b, (alpha * 255.0) as u8));
+    }
+
+    pub fn draw_background(&self, draw: &mut RaylibDrawHandle) {
+        let Some(background) = &self.background else {
+            return;
+        };
+        let (w, h) = (draw.get_screen_width(), draw.get_screen_height());
+        draw.draw_texture_pro(
+            background,
+            Rectangle::new(0.0, 0.0, background.width as f32, background.height as f32),
+            Rectangle::new(0.0, 0.0, w as f32, h as f32),
+            Vector2::zero(),
+            0.0,
+            Color::WHITE,
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::mpsc::{self, Receiver, SyncSender, TrySendError},
+    thread,
+};
+
+use serde::{Deserialize, Serialize};
+use tetris_core::{
+    cell::Cell,
+    prelude::{Brick, Color, Tetris},
+    traits::{HasSize, IterateDimensions, Randomizer},
+};
+
+/// How many snapshots may be waiting for a slow spectator before frames get dropped.
+const CLIENT_BACKLOG: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotCell {
+    Normal { kind: usize, color: [u8; 3] },
+    Ghost,
+    Garbage,
+}
+
+impl From<Cell> for SnapshotCell {
+    fn from(cell: Cell) -> Self {
+        match cell {
+            Cell::Normal {
+                kind,
+                color: Color(r, g, b),
+            } => Self::Normal {
+                kind,
+                color: [r, g, b],
+            },
+            Cell::Ghost => Self::Ghost,
+            Cell::Garbage => Self::Garbage,
+        }
+    }
+}
+
+impl From<SnapshotCell> for Cell {
+    fn from(cell: SnapshotCell) -> Self {
+        match cell {
+            SnapshotCell::Normal {
+                kind,
+                color: [r, g, b],
+            } => Cell::Normal {
+                kind,
+                color: Color(r, g, b),
+            },
+            SnapshotCell::Ghost => Cell::Ghost,
+            SnapshotCell::Garbage => Cell::Garbage,
+        }
+    }
+}
+
+/// A piece laid out row by row, as sent over the wire.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotBrick {
+    pub size: (i32, i32),
+    pub cells: Vec<Option<SnapshotCell>>,
+}
+
+impl SnapshotBrick {
+    fn capture(item: &impl IterateDimensions<Output = Option<Cell>>) -> Self {
+        let mut cells = Vec::with_capacity((item.width() * item.height()) as usize);
+        item.iter_dim(|_, _, c| cells.push(c.map(SnapshotCell::from)));
+        Self {
+            size: item.size(),
+            cells,
+        }
+    }
+}
+
+impl From<Brick> for SnapshotBrick {
+    fn from(brick: Brick) -> Self {
+        Self::capture(&brick)
+    }
+}
+
+impl HasSize for SnapshotBrick {
+    fn width(&self) -> i32 {
+        self.size.0
+    }
+
+    fn height(&self) -> i32 {
+        self.size.1
+    }
+}
+
+impl IterateDimensions for SnapshotBrick {
+    type Output = Option<Cell>;
+
+    fn get_item(&self, x: i32, y: i32) -> Self::Output {
+        self.cells
+            .get((y * self.width() + x) as usize)
+            .copied()
+            .flatten()
+            .map(Cell::from)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotPiece {
+    pub position: (i32, i32),
+    pub brick: SnapshotBrick,
+}
+
+/// Everything a spectator needs to redraw a game, one per line of the stream.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub board: SnapshotBrick,
+    /// Unset between pieces.
+    pub piece: Option<SnapshotPiece>,
+    pub ghost: Option<SnapshotPiece>,
+    pub hold: Option<SnapshotBrick>,
+    pub queue: Vec<SnapshotBrick>,
+    pub score: u32,
+}
+
+impl<R: Randomizer> From<&Tetris<R>> for Snapshot {
+    fn from(tetris: &Tetris<R>) -> Self {
+        let player = tetris.player();
+        let ghost = tetris.ghost();
+        Self {
+            board: SnapshotBrick::capture(tetris.board()),
+            piece: (!tetris.spawning()).then(|| SnapshotPiece {
+                position: player.position(),
+                brick: player.brick().into(),
+            }),
+            ghost: (!tetris.spawning()).then(|| SnapshotPiece {
+                position: ghost.position(),
+                brick: ghost.brick().into(),
+            }),
+            hold: tetris.held().map(SnapshotBrick::from),
+            queue: tetris
+                .queue()
+                .iter()
+                .copied()
+                .map(SnapshotBrick::from)
+                .collect(),
+            score: tetris.score(),
+        }
+    }
+}
+
+impl HasSize for Snapshot {
+    fn width(&self) -> i32 {
+        self.board.width()
+    }
+
+    fn height(&self) -> i32 {
+        self.board.height()
+    }
+}
+
+impl IterateDimensions for Snapshot {
+    type Output = Option<Cell>;
+
+    fn get_item(&self, x: i32, y: i32) -> Self::Output {
+        [&self.piece, &self.ghost]
+            .into_iter()
+            .flatten()
+            .find_map(|p| {
+                let (px, py) = (x - p.position.0, y - p.position.1);
+                if px >= 0 && px < p.brick.width() && py >= 0 && py < p.brick.height() {
+                    p.brick.get_item(px, py)
+                } else {
+                    None
+                }
+            })
+            .or_else(|| self.board.get_item(x, y))
+    }
+}
+
+/// Broadcasts snapshots as newline-delimited JSON to every connected spectator.
+#[derive(Debug)]
+pub struct SpectatorServer {
+    listener: TcpListener,
+    clients: Vec<SyncSender<String>>,
+    last: Option<Snapshot>,
+}
+
+impl SpectatorServer {
+    pub fn bind(addr: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            clients: vec![],
+            last: None,
+        })
+    }
+
+    fn accept_pending(&mut self) {
+        while let Ok((stream, _)) = self.listener.accept() {
+            let (tx, rx) = mpsc::sync_channel(CLIENT_BACKLOG);
+            thread::spawn(move || serve_client(stream, rx));
+            if let Some(last) = &self.last {
+                if let Ok(line) = serde_json::to_string(last) {
+                    let _ = tx.try_send(line);
+                }
+            }
+            self.clients.push(tx);
+        }
+    }
+
+    /// Sends the snapshot to all spectators, skipping it if nothing changed since the last one.
+    pub fn broadcast(&mut self, snapshot: Snapshot) {
+        self.accept_pending();
+        if self.last.as_ref() == Some(&snapshot) {
+            return;
+        }
+
+        if let Ok(line) = serde_json::to_string(&snapshot) {
+            self.clients.retain(|tx| match tx.try_send(line.clone()) {
+                Ok(()) | Err(TrySendError::Full(_)) => true,
+                Err(TrySendError::Disconnected(_)) => false,
+            });
+        }
+        self.last = Some(snapshot);
+    }
+}
+
+fn serve_client(mut stream: TcpStream, rx: Receiver<String>) {
+    // Accepted sockets inherit non-blocking mode from the listener on some platforms, the
+    // writes below wait instead.
+    if stream.set_nonblocking(false).is_err() {
+        return;
+    }
+    let _ = stream.set_nodelay(true);
+    for line in rx {
+        if writeln!(stream, "{line}").is_err() {
+            break;
+        }
+    }
+}
+
+/// Read-only connection to a [`SpectatorServer`].
+#[derive(Debug)]
+pub struct SpectatorClient {
+    snapshots: Receiver<Snapshot>,
+    latest: Option<Snapshot>,
+    connected: bool,
+}
+
+impl SpectatorClient {
+    pub fn connect(addr: &str) -> io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else { break };
+                if let Ok(snapshot) = serde_json::from_str::<Snapshot>(&line) {
+                    if tx.send(snapshot).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            snapshots: rx,
+            latest: None,
+            connected: true,
+        })
+    }
+
+    /// Drains the stream and returns the most recent snapshot received so far.
+    pub fn latest(&mut self) -> Option<&Snapshot> {
+        loop {
+            match self.snapshots.try_recv() {
+                Ok(snapshot) => self.latest = Some(snapshot),
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    self.connected = false;
+                    break;
+                }
+            }
+        }
+        self.latest.as_ref()
+    }
+
+    pub fn connected(&self) -> bool {
+        self.connected
+    }
+}
@@ -0,0 +1,32 @@
+/// Simulation steps per second, whatever the display refresh rate.
+pub const STEPS_PER_SECOND: f32 = 60.0;
+
+/// Longest stretch of real time caught up on at once, so a stall does not fast forward the game.
+const MAX_FRAME_TIME: f32 = 0.25;
+
+/// Turns real frame times into a whole number of fixed simulation steps.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixedTimestep {
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    /// Adds `frame_time` seconds and returns how many steps to simulate for them.
+    pub fn advance(&mut self, frame_time: f32) -> u32 {
+        let step = 1.0 / STEPS_PER_SECOND;
+        self.accumulator += frame_time.clamp(0.0, MAX_FRAME_TIME);
+        let steps = (self.accumulator / step) as u32;
+        self.accumulator -= steps as f32 * step;
+        steps
+    }
+
+    /// How far the renderer is between the last step and the next one, from 0 to 1.
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator * STEPS_PER_SECOND).clamp(0.0, 1.0)
+    }
+
+    /// Drops leftover time, e.g. while the game is paused.
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+    }
+}